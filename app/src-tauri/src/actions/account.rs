@@ -1,6 +1,9 @@
 use ethers::signers::LocalWallet;
-use hyperliquid_rust_sdk::{BaseUrl, ExchangeClient, InfoClient};
+use hyperliquid_rust_sdk::{
+    BaseUrl, ExchangeClient, FileNonceManager, InfoClient, NonceManager, NormalizationMode,
+};
 use reqwest::{Client, Proxy};
+use std::sync::{Arc, OnceLock};
 
 use crate::types::{Account, BatchAccount, Handlers, ProxyDTO};
 use crate::utils::str::private_key_slice;
@@ -68,6 +71,22 @@ pub async fn get_info_client(account: &Account) -> Result<InfoClient, String> {
     }
 }
 
+static NONCE_MANAGER: OnceLock<Option<Arc<dyn NonceManager>>> = OnceLock::new();
+
+/// Nonces are kept on disk so that a restart doesn't reuse ones that were already sent.
+/// Falls back to the SDK's in-memory nonces if the file can't be read.
+fn nonce_manager() -> Option<Arc<dyn NonceManager>> {
+    NONCE_MANAGER
+        .get_or_init(|| match FileNonceManager::new("nonces.json") {
+            Ok(nonce_manager) => Some(Arc::new(nonce_manager)),
+            Err(e) => {
+                error!("Error loading nonces: {:?}", e);
+                None
+            }
+        })
+        .clone()
+}
+
 pub async fn get_exchange_client(account: &Account) -> Result<ExchangeClient, String> {
     match ExchangeClient::new(
         Some(account.client.clone()),
@@ -75,10 +94,14 @@ pub async fn get_exchange_client(account: &Account) -> Result<ExchangeClient, St
         Some(BaseUrl::Mainnet),
         None,
         None,
+        nonce_manager(),
     )
     .await
     {
-        Ok(exchange_client) => Ok(exchange_client),
+        Ok(mut exchange_client) => {
+            exchange_client.normalization = Some(NormalizationMode::Lenient);
+            Ok(exchange_client)
+        }
         Err(e) => {
             error!(
                 "Error creating exchange client for {}: {:?}",
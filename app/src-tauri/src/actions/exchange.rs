@@ -1,14 +1,15 @@
 use async_recursion::async_recursion;
+use ethers::types::H160;
 use hyperliquid_rust_sdk::{
-    AssetPosition, ClientCancelRequest, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient, ExchangeDataStatus, ExchangeResponseStatus, FilledOrder, InfoClient, RestingOrder
+    AssetPosition, ClientCancelRequest, ClientLimit, ClientOrderDecimal, ClientOrderRequestDecimal, ClientTwapRequestDecimal, ExchangeClient, ExchangeDataStatus, ExchangeResponseStatus, FilledOrder, InfoClient, RestingOrder, TwapStatus
 };
 use log::{error, info, warn};
+use rust_decimal::prelude::*;
 
-use crate::actions::info::slippage_price;
+use crate::actions::info::DEFAULT_SLIPPAGE;
 
 use crate::types::DefaultPair;
 
-use crate::utils::num::next_decimal;
 use crate::utils::parsers::parse_liq_px;
 
 use super::info::get_position;
@@ -16,39 +17,56 @@ use super::info::get_position;
 pub async fn open_order(
     default_pair: DefaultPair,
     exchange_client: &ExchangeClient,
-    info_client: &InfoClient,
     is_buy: bool,
-    limit_px: Option<f64>,
+    limit_px: Option<Decimal>,
 ) -> Result<FilledOrder, String> {
-    let is_limit = limit_px.is_some();
-    let limit_px = if is_limit {
-        next_decimal(limit_px.unwrap(), is_buy)
-    } else {
-        slippage_price(info_client, &default_pair.asset, is_buy, None).await
-    };
-
-    let order = ClientOrderRequest {
-        sz: default_pair.sz,
-        cloid: None,
-        asset: default_pair.asset.clone(),
-        is_buy,
-        reduce_only: default_pair.reduce_only,
-        order_type: ClientOrder::Limit(ClientLimit {
-            tif: default_pair.order_type.clone(),
-        }),
-        limit_px,
-    };
+    let sz = default_pair.sz;
 
-    let response = exchange_client.order(order, None).await;
+    let response = match limit_px {
+        Some(limit_px) => {
+            let limit_px = exchange_client
+                .assets()
+                .await
+                .map_err(|e| e.to_string())?
+                .normalizer
+                .offset_px_decimal(&default_pair.asset, limit_px, if is_buy { -1 } else { 1 })
+                .map_err(|e| e.to_string())?;
+
+            let order = ClientOrderRequestDecimal {
+                sz,
+                cloid: None,
+                asset: default_pair.asset.clone(),
+                is_buy,
+                reduce_only: default_pair.reduce_only,
+                order_type: ClientOrderDecimal::Limit(ClientLimit {
+                    tif: default_pair.order_type,
+                }),
+                limit_px,
+            };
+
+            exchange_client.order_decimal(order, None, None).await
+        }
+        None if default_pair.reduce_only => {
+            return Err("Reduce only market orders go through close_position".to_string());
+        }
+        None => {
+            exchange_client
+                .market_open_decimal(&default_pair.asset, is_buy, sz, Some(DEFAULT_SLIPPAGE), None, None)
+                .await
+        }
+    }
+    .map_err(|e| e.to_string())?;
 
     info!("{:#?}", response);
 
-    let response = response.unwrap();
+    parse_filled_order(response)
+}
 
+fn parse_filled_order(response: ExchangeResponseStatus) -> Result<FilledOrder, String> {
     match response {
         ExchangeResponseStatus::Ok(exchange_response) => {
             match &exchange_response.data.unwrap().statuses[0] {
-                ExchangeDataStatus::Error(e) => return Err(e.clone()),
+                ExchangeDataStatus::Error(e) => return Err(e.to_string()),
                 ExchangeDataStatus::Filled(f) => return Ok(f.clone()),
                 _ => return Err("Smth went wrong with order".to_string()),
             }
@@ -69,29 +87,30 @@ pub async fn close_position(
     public_address: String,
 ) -> Result<(), String> {
     let asset = position.position.coin.clone();
-    let szi = position.position.szi.clone().parse::<f64>().unwrap();
-    let position_is_buy = if szi > 0.0 { true } else { false };
-    let position_pair = DefaultPair {
-        asset: asset.to_string(),
-        sz: szi.abs(),
-        reduce_only: false,
-        order_type: "FrontendMarket".to_string(),
-    };
+    let szi = position
+        .position
+        .szi_decimal()
+        .map_err(|e| e.to_string())?;
+
+    let user = public_address
+        .parse::<H160>()
+        .map_err(|e| e.to_string())?;
 
     info!("Closing position {position:?} for {public_address}, unit: {asset}");
 
-    let r = open_order(
-        position_pair,
-        exchange_client,
-        info_client,
-        !position_is_buy,
-        None,
-    )
-    .await;
+    // The client signs with an agent key, so the position is looked up for the account itself
+    let response = exchange_client
+        .market_close(&asset, None, Some(DEFAULT_SLIPPAGE), Some(user), None, None)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    info!("{:#?}", response);
+
+    let r = parse_filled_order(response);
 
     match r {
         Ok(f) => {
-            if f.total_sz.parse::<f64>().unwrap() == szi.abs() {
+            if f.total_sz_decimal().map_err(|e| e.to_string())? == szi.abs() {
                 info!("Position closed for {public_address}, unit: {asset}, filled order: {f:?}");
 
                 Ok(())
@@ -115,39 +134,71 @@ pub async fn close_position(
     }
 }
 
-pub async fn open_limit_order(
+pub async fn open_twap_order(
     default_pair: DefaultPair,
     exchange_client: &ExchangeClient,
-    info_client: &InfoClient,
     is_buy: bool,
-    limit_px: Option<f64>,
-) -> Result<RestingOrder, String> {
-    let is_limit = limit_px.is_some();
-    let limit_px = if is_limit {
-        next_decimal(limit_px.unwrap(), is_buy)
-    } else {
-        slippage_price(info_client, &default_pair.asset, is_buy, None).await
+    minutes: u32,
+) -> Result<u64, String> {
+    let twap = ClientTwapRequestDecimal {
+        asset: default_pair.asset,
+        is_buy,
+        reduce_only: default_pair.reduce_only,
+        sz: default_pair.sz,
+        minutes,
+        randomize: true,
     };
 
-    let order = ClientOrderRequest {
+    let response = exchange_client
+        .twap_order_decimal(twap, None, None)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    info!("{:#?}", response);
+
+    match response {
+        ExchangeResponseStatus::Ok(twap_response) => match twap_response.data.status {
+            TwapStatus::Running(running) => Ok(running.twap_id),
+            TwapStatus::Error(e) => Err(e.to_string()),
+            TwapStatus::Success => Err("TWAP order was not started".to_string()),
+        },
+        ExchangeResponseStatus::Err(e) => Err(e.to_string()),
+    }
+}
+
+pub async fn open_limit_order(
+    default_pair: DefaultPair,
+    exchange_client: &ExchangeClient,
+    is_buy: bool,
+    limit_px: Decimal,
+) -> Result<RestingOrder, String> {
+    let limit_px = exchange_client
+        .assets()
+        .await
+        .map_err(|e| e.to_string())?
+        .normalizer
+        .offset_px_decimal(&default_pair.asset, limit_px, if is_buy { -1 } else { 1 })
+        .map_err(|e| e.to_string())?;
+
+    let order = ClientOrderRequestDecimal {
         sz: default_pair.sz,
         cloid: None,
         asset: default_pair.asset.clone(),
         is_buy,
         reduce_only: default_pair.reduce_only,
-        order_type: ClientOrder::Limit(ClientLimit {
-            tif: default_pair.order_type.clone(),
+        order_type: ClientOrderDecimal::Limit(ClientLimit {
+            tif: default_pair.order_type,
         }),
         limit_px,
     };
 
-    let response = exchange_client.order(order, None).await.unwrap();
+    let response = exchange_client.order_decimal(order, None, None).await.unwrap();
 
     match response {
         ExchangeResponseStatus::Ok(exchange_response) => {
             match &exchange_response.data.unwrap().statuses[0] {
                 ExchangeDataStatus::Resting(r) => return Ok(r.clone()),
-                ExchangeDataStatus::Error(e) => return Err(e.clone()),
+                ExchangeDataStatus::Error(e) => return Err(e.to_string()),
                 // ExchangeDataStatus::Filled(f) => return Ok(f.clone()),
                 _ => return Err("Smth went wrong with order".to_string()),
             }
@@ -172,14 +223,7 @@ pub async fn open_position(
         position_pair.asset
     );
 
-    let r = open_order(
-        position_pair.clone(),
-        exchange_client,
-        info_client,
-        is_buy,
-        None,
-    )
-    .await;
+    let r = open_order(position_pair.clone(), exchange_client, is_buy, None).await;
 
     match r {
         Ok(f) => {
@@ -187,7 +231,7 @@ pub async fn open_position(
                 .await
                 .unwrap();
 
-            if pos.position.szi.parse::<f64>().unwrap().abs() != position_pair.sz {
+            if pos.position.szi_decimal().unwrap().abs() != position_pair.sz {
                 error!("Current opened position size and requested size are not equal for {public_address}, 
                 unit: {}, filled: {f:?}, current position: {pos:?}", position_pair.asset);
 
@@ -199,7 +243,7 @@ pub async fn open_position(
                 ));
             }
 
-            if position_pair.sz != f.total_sz.parse::<f64>().unwrap() {
+            if position_pair.sz != f.total_sz_decimal().unwrap() {
                 error!(
                     "Position not fully opened for {public_address}, unit: {}, filled: {f:?}",
                     position_pair.asset
@@ -229,11 +273,11 @@ pub async fn open_position(
 }
 
 pub async fn modify_limit_order(
-    new_order: ClientOrderRequest,
+    new_order: ClientOrderRequestDecimal,
     oid: u64,
     exchange_client: &ExchangeClient,
 ) -> Result<RestingOrder, String> {
-    let response = exchange_client.modify_order(oid, new_order, None).await;
+    let response = exchange_client.modify_order_decimal(oid, new_order, None, None).await;
 
     info!("{:#?}", response);
 
@@ -243,7 +287,7 @@ pub async fn modify_limit_order(
         ExchangeResponseStatus::Ok(exchange_response) => {
             match &exchange_response.data.unwrap().statuses[0] {
                 ExchangeDataStatus::Resting(r) => return Ok(r.clone()),
-                ExchangeDataStatus::Error(e) => return Err(e.clone()),
+                ExchangeDataStatus::Error(e) => return Err(e.to_string()),
                 // ExchangeDataStatus::Filled(f) => return Ok(f.clone()),
                 _ => return Err("Smth went wrong with order".to_string()),
             }
@@ -260,13 +304,13 @@ pub async fn cancel_limit_order(exchange_client: &ExchangeClient, asset: String,
     let response = exchange_client.cancel(ClientCancelRequest {
         asset,
         oid
-    }, None).await.unwrap();
+    }, None, None).await.unwrap();
 
     match response {
         ExchangeResponseStatus::Ok(exchange_response) => {
             match &exchange_response.data.unwrap().statuses[0] {
                 ExchangeDataStatus::Success => return Ok(()),
-                ExchangeDataStatus::Error(e) => return Err(e.clone()),
+                ExchangeDataStatus::Error(e) => return Err(e.to_string()),
                 // ExchangeDataStatus::Filled(f) => return Ok(f.clone()),
                 _ => return Err("Smth went wrong while canceling order".to_string()),
             }
@@ -6,19 +6,16 @@ use hyperliquid_rust_sdk::{
 
 use itertools::Itertools;
 use log::{error, info};
-use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::prelude::*;
 use std::{collections::HashMap, str::FromStr};
 use tokio::sync::mpsc::unbounded_channel;
 
 use crate::{
     types::{ConvertedOrderInfo, OrderBook},
-    utils::{
-        convert_types::{convert_order_status, convert_public_address},
-        num::round_num_by_hyper_liquid,
-    },
+    utils::convert_types::{convert_order_status, convert_public_address},
 };
 
-const DEFAULT_SLIPPAGE: f64 = 0.001; // 0.001 0.1%
+pub const DEFAULT_SLIPPAGE: f64 = 0.001; // 0.001 0.1%
 const FEES: f64 = 0.000336; // 0.000336
 const BALANCE_LIMIT: f64 = 0.0; // TODO: change to 50.0
 
@@ -99,31 +96,6 @@ pub async fn get_l2_book(info_client: &InfoClient, coin: &str) -> OrderBook {
     levels
 }
 
-pub async fn slippage_price(
-    info_client: &InfoClient,
-    asset: &str,
-    is_buy: bool,
-    px: Option<f64>,
-) -> f64 {
-    let coin_mid_price = &get_all_mids(info_client).await[asset];
-
-    let mut cpx: f64;
-
-    if px.is_none() {
-        cpx = coin_mid_price.parse::<f64>().unwrap();
-    } else {
-        cpx = px.unwrap();
-    }
-
-    cpx *= if is_buy {
-        1.0 + DEFAULT_SLIPPAGE
-    } else {
-        1.0 - DEFAULT_SLIPPAGE
-    };
-
-    round_num_by_hyper_liquid(cpx)
-}
-
 pub async fn can_open_position(
     info_client: &InfoClient,
     public_address: &str,
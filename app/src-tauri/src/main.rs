@@ -8,7 +8,6 @@ use fern::colors::{Color, ColoredLevelConfig};
 use std::panic;
 use std::thread::sleep;
 use std::time::Duration;
-use utils::num::next_decimal;
 
 mod actions;
 mod dto_types;
@@ -1,9 +1,10 @@
-use crate::actions::exchange::{cancel_limit_order, open_limit_order};
+use crate::actions::exchange::{modify_limit_order, open_limit_order};
 use crate::actions::info::get_l2_book;
 use crate::types::{Bid, DefaultPair, Handlers};
-use crate::utils::num::next_decimal;
 use crate::utils::parsers::parse_l2_book;
+use hyperliquid_rust_sdk::{ClientLimit, ClientOrderDecimal, ClientOrderRequestDecimal, Tif};
 use log::{error, info};
+use rust_decimal::prelude::*;
 
 pub async fn open_spot_order_service(handlers: &Handlers, bid: Bid) -> Result<u64, String> {
     let price = parse_l2_book(
@@ -14,18 +15,13 @@ pub async fn open_spot_order_service(handlers: &Handlers, bid: Bid) -> Result<u6
     let order = DefaultPair {
         asset: bid.asset,
         reduce_only: false,
-        sz: bid.sz,
-        order_type: "Gtc".to_string(),
+        sz: Decimal::from_f64(bid.sz)
+            .unwrap()
+            .round_dp(bid.sz_decimals),
+        order_type: Tif::Gtc,
     };
 
-    let r = open_limit_order(
-        order,
-        &handlers.exchange_client,
-        &handlers.info_client,
-        bid.is_buy,
-        Some(price),
-    )
-    .await?;
+    let r = open_limit_order(order, &handlers.exchange_client, bid.is_buy, price).await?;
 
     Ok(r.oid)
 }
@@ -35,28 +31,33 @@ pub async fn modify_spot_order_service(
     bid: Bid,
     oid: u64,
 ) -> Result<u64, String> {
-    cancel_limit_order(&handlers.exchange_client, bid.asset.clone(), oid).await?;
-
     let price = parse_l2_book(
         get_l2_book(&handlers.info_client, &bid.asset).await,
         bid.is_buy,
     );
 
-    let order = DefaultPair {
+    let limit_px = handlers
+        .exchange_client
+        .assets()
+        .await
+        .map_err(|e| e.to_string())?
+        .normalizer
+        .offset_px_decimal(&bid.asset, price, if bid.is_buy { -1 } else { 1 })
+        .map_err(|e| e.to_string())?;
+
+    let order = ClientOrderRequestDecimal {
+        sz: Decimal::from_f64(bid.sz)
+            .unwrap()
+            .round_dp(bid.sz_decimals),
+        cloid: None,
         asset: bid.asset,
+        is_buy: bid.is_buy,
         reduce_only: false,
-        sz: bid.sz,
-        order_type: "Gtc".to_string(),
+        order_type: ClientOrderDecimal::Limit(ClientLimit { tif: Tif::Gtc }),
+        limit_px,
     };
 
-    let r = open_limit_order(
-        order,
-        &handlers.exchange_client,
-        &handlers.info_client,
-        bid.is_buy,
-        Some(price),
-    )
-    .await?;
+    let r = modify_limit_order(order, oid, &handlers.exchange_client).await?;
 
     Ok(r.oid)
 }
@@ -1,10 +1,10 @@
 use futures::future::{self, join_all, FutureExt};
-use hyperliquid_rust_sdk::AssetPosition;
+use hyperliquid_rust_sdk::{AssetPosition, Tif};
 use itertools::Itertools;
 use log::{error, info, warn};
 use rust_decimal::prelude::*;
 
-use crate::actions::exchange::{close_position, open_position};
+use crate::actions::exchange::{close_position, open_position, open_twap_order};
 use crate::actions::info::{can_open_position, get_account_balance, get_position};
 use crate::types::{DefaultPair, Handlers, Unit};
 use crate::utils::rand::{get_rand_is_buy_fat, get_rand_k_4, get_rand_k_6, rand_idx};
@@ -16,6 +16,7 @@ pub async fn create_unit_service(handlers: &Vec<Handlers>, unit: Unit) -> Result
         leverage,
         sz_decimals,
         smart_balance_usage,
+        twap_minutes,
     } = unit;
 
     warn!(
@@ -49,7 +50,7 @@ pub async fn create_unit_service(handlers: &Vec<Handlers>, unit: Unit) -> Result
 
     let _ = join_all(handlers.iter().map(|h| {
         h.exchange_client
-            .update_leverage(leverage, &asset, false, None)
+            .update_leverage(leverage, &asset, false, None, None)
     }))
     .await;
 
@@ -78,6 +79,59 @@ pub async fn create_unit_service(handlers: &Vec<Handlers>, unit: Unit) -> Result
         ));
     }
 
+    if let Some(minutes) = twap_minutes {
+        if handlers.len() != 2 {
+            return Err(format!(
+                "TWAP units need 2 accounts, got {}, unit: {asset}",
+                handlers.len()
+            ));
+        }
+
+        let twaps = join_all(handlers.iter().enumerate().map(|(i, h)| {
+            open_twap_order(
+                DefaultPair {
+                    asset: asset.to_string(),
+                    sz: Decimal::from_f64(sz)
+                        .unwrap()
+                        .round_dp(sz_decimals),
+                    reduce_only: false,
+                    order_type: Tif::FrontendMarket,
+                },
+                &h.exchange_client,
+                i == 0,
+                minutes,
+            )
+        }))
+        .await;
+
+        if let Some(Err(e)) = twaps.iter().find(|twap| twap.is_err()) {
+            error!(
+                "Error opening TWAP orders for {}: {e}, unit: {asset}",
+                handlers
+                    .iter()
+                    .map(|h| h.public_address.clone())
+                    .join(" & ")
+            );
+
+            let _ = join_all(handlers.iter().zip(twaps.iter()).filter_map(|(h, twap)| {
+                twap.as_ref()
+                    .ok()
+                    .map(|&twap_id| h.exchange_client.twap_cancel(&asset, twap_id, None, None))
+            }))
+            .await;
+
+            return Err(format!(
+                "Error opening TWAP orders for {}, unit: {asset}",
+                handlers
+                    .iter()
+                    .map(|h| h.public_address.clone())
+                    .join(" & ")
+            ));
+        }
+
+        return Ok(());
+    }
+
     let poss: Vec<Result<AssetPosition, String>>;
 
     if handlers.len() == 2 {
@@ -89,11 +143,9 @@ pub async fn create_unit_service(handlers: &Vec<Handlers>, unit: Unit) -> Result
                     asset: asset.to_string(),
                     sz: Decimal::from_f64(sz)
                         .unwrap()
-                        .round_dp(sz_decimals)
-                        .to_f64()
-                        .unwrap(),
+                        .round_dp(sz_decimals),
                     reduce_only: false,
-                    order_type: "FrontendMarket".to_string(),
+                    order_type: Tif::FrontendMarket,
                 },
                 h.public_address.clone(),
                 i == 0,
@@ -109,6 +161,7 @@ pub async fn create_unit_service(handlers: &Vec<Handlers>, unit: Unit) -> Result
                 sz_decimals,
                 leverage,
                 smart_balance_usage,
+                twap_minutes,
             },
         )
         .await;
@@ -197,6 +250,7 @@ pub async fn close_and_create_unit_service(
         leverage,
         sz_decimals,
         smart_balance_usage,
+        twap_minutes,
     } = unit;
 
     warn!(
@@ -228,6 +282,7 @@ pub async fn close_and_create_unit_service(
                     leverage,
                     sz_decimals,
                     smart_balance_usage,
+                    twap_minutes,
                 },
             )
             .await
@@ -287,11 +342,9 @@ pub async fn open_rand_poss_service(
                         asset: unit.asset.to_string(),
                         sz: Decimal::from_f64(unit.sz * k / 100.0)
                             .unwrap()
-                            .round_dp(unit.sz_decimals)
-                            .to_f64()
-                            .unwrap(),
+                            .round_dp(unit.sz_decimals),
                         reduce_only: false,
-                        order_type: "FrontendMarket".to_string(),
+                        order_type: Tif::FrontendMarket,
                     },
                     h.public_address.clone(),
                     is_buy,
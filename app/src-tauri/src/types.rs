@@ -1,8 +1,11 @@
 use std::collections::HashMap;
 
 use ethers::signers::LocalWallet;
-use hyperliquid_rust_sdk::{AssetPosition, BasicOrderInfo, ExchangeClient, InfoClient, Level};
+use hyperliquid_rust_sdk::{
+    AssetPosition, BasicOrderInfo, ExchangeClient, InfoClient, Level, Tif,
+};
 use reqwest::Client;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -10,8 +13,8 @@ use uuid::Uuid;
 pub struct DefaultPair {
     pub asset: String,
     pub reduce_only: bool,
-    pub sz: f64,
-    pub order_type: String,
+    pub sz: Decimal,
+    pub order_type: Tif,
 }
 
 pub enum OrderType {
@@ -80,6 +83,10 @@ pub struct Unit {
     pub leverage: u32,
     pub sz_decimals: u32,
     pub smart_balance_usage: bool,
+    /// Open the legs with exchange-native TWAP orders over this many minutes instead of
+    /// market orders
+    #[serde(default)]
+    pub twap_minutes: Option<u32>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
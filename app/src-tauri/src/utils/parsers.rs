@@ -1,20 +1,16 @@
 use hyperliquid_rust_sdk::AssetPosition;
+use rust_decimal::Decimal;
 
 use crate::types::OrderBook;
 
-pub fn parse_liq_px(pos: &AssetPosition) -> f64 {
-    pos.position
-        .liquidation_px
-        .clone()
-        .unwrap()
-        .parse::<f64>()
-        .unwrap()
+pub fn parse_liq_px(pos: &AssetPosition) -> Decimal {
+    pos.position.liquidation_px_decimal().unwrap().unwrap()
 }
 
-pub fn parse_l2_book(orderBook: OrderBook, is_buy: bool) -> f64 {
+pub fn parse_l2_book(orderBook: OrderBook, is_buy: bool) -> Decimal {
     let buy = orderBook[0][0].clone();
     let sell = orderBook[1][0].clone();
-    let price = if is_buy { buy.px } else { sell.px };
+    let price = if is_buy { buy.px_decimal() } else { sell.px_decimal() };
 
-    price.parse::<f64>().unwrap()
+    price.unwrap()
 }
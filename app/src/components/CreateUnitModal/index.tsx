@@ -155,6 +155,7 @@ export const CreateUnitModal: React.FC<{
     sz: number
     leverage: number
     timing: number
+    twapMinutes: number
   }) => void
   defaultTiming: number
 }> = ({
@@ -170,6 +171,7 @@ export const CreateUnitModal: React.FC<{
     timing: defaultTiming,
     sz: 0,
     leverage: 1,
+    twapMinutes: 0,
   })
 
   const [assetPrice, setAssetPrice] = useState(0)
@@ -205,7 +207,7 @@ export const CreateUnitModal: React.FC<{
   }
 
   const onChange = (
-    key: 'asset' | 'sz' | 'leverage' | 'timing',
+    key: 'asset' | 'sz' | 'leverage' | 'timing' | 'twapMinutes',
     v: string | number,
   ) => {
     if (key === 'asset' && typeof v === 'string') {
@@ -324,6 +326,20 @@ export const CreateUnitModal: React.FC<{
               onChange={e => onChange('timing', Number(e.target.value))}
             />
           </Box>
+          {accountsCount === 2 && (
+            <Box sx={{ width: '100%' }}>
+              <TextField
+                label='TWAP (mins, 0 = market)'
+                type='number'
+                size='small'
+                value={form.twapMinutes}
+                variant='outlined'
+                onChange={e =>
+                  onChange('twapMinutes', Number(e.target.value))
+                }
+              />
+            </Box>
+          )}
         </Box>
         <Box>
           <Typography>
@@ -80,6 +80,7 @@ export const Batch: React.FC<{
     sz: number
     leverage: number
     timing: number
+    twapMinutes: number
   }) => {
     setModalId(null)
     const promise = createUnit(form)
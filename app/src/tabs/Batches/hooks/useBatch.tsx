@@ -29,6 +29,7 @@ interface CreateUnitPayload {
   sz: number
   leverage: number
   timing: number
+  twapMinutes?: number
 }
 
 interface ReturnType {
@@ -307,7 +308,13 @@ export const useBatch = ({
   }, [updateLoop])
 
   const createUnit = useCallback(
-    async ({ asset, sz, leverage, timing }: CreateUnitPayload) => {
+    async ({
+      asset,
+      sz,
+      leverage,
+      timing,
+      twapMinutes,
+    }: CreateUnitPayload) => {
       setCreatingUnits(prev => [...prev, asset])
 
       await setUnitSize(asset, sz)
@@ -323,6 +330,7 @@ export const useBatch = ({
           leverage,
           smart_balance_usage: smartBalanceUsage,
           sz_decimals,
+          twap_minutes: twapMinutes || null,
         },
       }).finally(async () => {
         setTimings(asset, timing, Date.now())
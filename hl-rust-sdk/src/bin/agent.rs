@@ -1,7 +1,9 @@
 use log::info;
 
 use ethers::signers::{LocalWallet, Signer};
-use hyperliquid_rust_sdk::{BaseUrl, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient};
+use hyperliquid_rust_sdk::{
+    BaseUrl, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient, Tif,
+};
 
 #[tokio::main]
 async fn main() {
@@ -11,25 +13,30 @@ async fn main() {
         .parse()
         .unwrap();
 
-    let exchange_client = ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None)
-        .await
-        .unwrap();
+    let exchange_client =
+        ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None, None)
+            .await
+            .unwrap();
 
     /*
         Create a new wallet with the agent.
         This agent cannot transfer or withdraw funds, but can for example place orders.
     */
 
-    let (private_key, response) = exchange_client.approve_agent(None).await.unwrap();
+    let (credentials, response) = exchange_client
+        .approve_named_agent("example", None)
+        .await
+        .unwrap();
     info!("Agent creation response: {response:?}");
 
-    let wallet: LocalWallet = private_key.parse().unwrap();
+    let wallet = credentials.wallet().unwrap();
 
     info!("Agent address: {:?}", wallet.address());
 
-    let exchange_client = ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None)
-        .await
-        .unwrap();
+    let agent_exchange_client =
+        ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None, None)
+            .await
+            .unwrap();
 
     let order = ClientOrderRequest {
         asset: "ETH".to_string(),
@@ -38,12 +45,21 @@ async fn main() {
         limit_px: 1795.0,
         sz: 0.01,
         cloid: None,
-        order_type: ClientOrder::Limit(ClientLimit {
-            tif: "Gtc".to_string(),
-        }),
+        order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
     };
 
-    let response = exchange_client.order(order, None).await.unwrap();
+    let response = agent_exchange_client
+        .order(order, None, None)
+        .await
+        .unwrap();
 
     info!("Order placed: {response:?}");
+
+    // Revoke the agent once it's no longer needed. Approving a new agent under the same name
+    // replaces it instead.
+    let response = exchange_client
+        .revoke_agent(credentials.name.as_deref(), None)
+        .await
+        .unwrap();
+    info!("Agent revoked: {response:?}");
 }
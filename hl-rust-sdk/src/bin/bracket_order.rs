@@ -0,0 +1,39 @@
+use ethers::signers::LocalWallet;
+use log::info;
+
+use hyperliquid_rust_sdk::{
+    BaseUrl, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient, Tif,
+};
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+        .parse()
+        .unwrap();
+
+    let exchange_client =
+        ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None, None)
+            .await
+            .unwrap();
+
+    let entry = ClientOrderRequest {
+        asset: "ETH".to_string(),
+        is_buy: true,
+        reduce_only: false,
+        limit_px: 1800.0,
+        sz: 0.01,
+        cloid: None,
+        order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+    };
+
+    // Take profit and stop loss legs are only triggered once the entry order is filled
+    let statuses = exchange_client
+        .bracket_order(entry, Some(1900.0), Some(1700.0), None, None)
+        .await
+        .unwrap();
+    info!("Entry: {:?}", statuses.entry);
+    info!("Take profit: {:?}", statuses.take_profit);
+    info!("Stop loss: {:?}", statuses.stop_loss);
+}
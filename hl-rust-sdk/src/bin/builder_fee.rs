@@ -0,0 +1,61 @@
+use ethers::{
+    signers::{LocalWallet, Signer},
+    types::H160,
+};
+use log::info;
+use std::str::FromStr;
+
+use hyperliquid_rust_sdk::{
+    BaseUrl, BuilderInfo, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient, InfoClient,
+    Tif,
+};
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+        .parse()
+        .unwrap();
+    let user = wallet.address();
+    let builder = H160::from_str("0x0D1d9635D0640821d15e323ac8AdADfA9c111414").unwrap();
+
+    let exchange_client =
+        ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None, None)
+            .await
+            .unwrap();
+    let info_client = InfoClient::new(None, Some(BaseUrl::Testnet)).await.unwrap();
+
+    let response = exchange_client
+        .approve_builder_fee(builder, "0.01%", None)
+        .await
+        .unwrap();
+    info!("Approve builder fee response: {response:?}");
+
+    // 0.01% is 10 tenths of a basis point
+    let max_builder_fee = info_client.max_builder_fee(user, builder).await.unwrap();
+    info!("Max builder fee: {max_builder_fee}");
+
+    let order = ClientOrderRequest {
+        asset: "ETH".to_string(),
+        is_buy: true,
+        reduce_only: false,
+        limit_px: 1800.0,
+        sz: 0.01,
+        cloid: None,
+        order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+    };
+    let response = exchange_client
+        .bulk_order_with_builder(
+            vec![order],
+            BuilderInfo {
+                builder,
+                fee: max_builder_fee,
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    info!("Order with builder response: {response:?}");
+}
@@ -10,15 +10,16 @@ async fn main() {
         .parse()
         .unwrap();
 
-    let exchange_client = ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None)
-        .await
-        .unwrap();
+    let exchange_client =
+        ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None, None)
+            .await
+            .unwrap();
 
     let usdc = 1.0; // 1 USD
     let to_perp = false;
 
     let res = exchange_client
-        .class_transfer(usdc, to_perp, None)
+        .class_transfer(usdc, to_perp, None, None)
         .await
         .unwrap();
     info!("Class transfer result: {res:?}");
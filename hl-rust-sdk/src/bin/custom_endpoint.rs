@@ -0,0 +1,27 @@
+use hyperliquid_rust_sdk::{BaseUrl, InfoClient, Message, Subscription};
+use log::info;
+use tokio::sync::mpsc::unbounded_channel;
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // E.g. a local node or a recording proxy in front of testnet
+    let base_url = BaseUrl::Custom {
+        http: "http://localhost:8080".to_string(),
+        ws: "ws://localhost:8080/ws".to_string(),
+        is_mainnet: false,
+    };
+    let mut info_client = InfoClient::new(None, Some(base_url)).await.unwrap();
+
+    let all_mids = info_client.all_mids().await.unwrap();
+    info!("All mids: {all_mids:?}");
+
+    let (sender, mut receiver) = unbounded_channel();
+    info_client
+        .subscribe(Subscription::AllMids, sender)
+        .await
+        .unwrap();
+    while let Some(Message::AllMids(all_mids)) = receiver.recv().await {
+        info!("Received mids data: {all_mids:?}");
+    }
+}
@@ -0,0 +1,46 @@
+use async_trait::async_trait;
+use ethers::{
+    signers::LocalWallet,
+    types::{Signature, H160, H256},
+};
+use log::info;
+
+use hyperliquid_rust_sdk::{BaseUrl, Error, ExchangeClient, HyperliquidSigner};
+
+/// Stands in for a signer backed by a KMS, an HSM or a remote signing service
+struct LoggingSigner {
+    inner: LocalWallet,
+}
+
+#[async_trait]
+impl HyperliquidSigner for LoggingSigner {
+    fn address(&self) -> H160 {
+        HyperliquidSigner::address(&self.inner)
+    }
+
+    async fn sign_hash(&self, hash: H256) -> Result<Signature, Error> {
+        info!("Signing digest {hash:?}");
+        HyperliquidSigner::sign_hash(&self.inner, hash).await
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+        .parse()
+        .unwrap();
+    let signer = LoggingSigner { inner: wallet };
+
+    let exchange_client =
+        ExchangeClient::new(None, signer, Some(BaseUrl::Testnet), None, None, None)
+            .await
+            .unwrap();
+
+    let response = exchange_client
+        .update_leverage(5, "ETH", false, None, None)
+        .await
+        .unwrap();
+    info!("Update leverage response: {response:?}");
+}
@@ -0,0 +1,50 @@
+use ethers::signers::LocalWallet;
+use log::info;
+
+use hyperliquid_rust_sdk::{
+    BaseUrl, ClientCancelRequest, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient,
+    ExchangeDataStatus, ExchangeResponseStatus, Tif,
+};
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+        .parse()
+        .unwrap();
+
+    // Orders are converted and signed against mainnet metadata, then logged instead of sent
+    let mut exchange_client =
+        ExchangeClient::new(None, wallet, Some(BaseUrl::Mainnet), None, None, None)
+            .await
+            .unwrap();
+    exchange_client.dry_run = true;
+
+    let order = ClientOrderRequest {
+        asset: "ETH".to_string(),
+        is_buy: true,
+        reduce_only: false,
+        limit_px: 1800.0,
+        sz: 0.01,
+        cloid: None,
+        order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+    };
+    let response = exchange_client.order(order, None, None).await.unwrap();
+    info!("Dry run order response: {response:?}");
+
+    let ExchangeResponseStatus::Ok(response) = response else {
+        panic!("dry runs always succeed");
+    };
+    let oid = match &response.data.unwrap().statuses[0] {
+        ExchangeDataStatus::Resting(order) => order.oid,
+        status => panic!("Error: {status:?}"),
+    };
+
+    let cancel = ClientCancelRequest {
+        asset: "ETH".to_string(),
+        oid,
+    };
+    let response = exchange_client.cancel(cancel, None, None).await.unwrap();
+    info!("Dry run cancel response: {response:?}");
+}
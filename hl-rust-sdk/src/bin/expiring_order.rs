@@ -0,0 +1,65 @@
+use ethers::signers::LocalWallet;
+use log::info;
+
+use hyperliquid_rust_sdk::{
+    ActionOptions, BaseUrl, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient, Tif,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+        .parse()
+        .unwrap();
+
+    let mut exchange_client =
+        ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None, None)
+            .await
+            .unwrap();
+
+    // Every L1 action sent by this client is rejected if it reaches the exchange more than 30
+    // seconds from now
+    exchange_client.expires_after = Some(now_ms() + 30_000);
+
+    let order = ClientOrderRequest {
+        asset: "ETH".to_string(),
+        is_buy: true,
+        reduce_only: false,
+        limit_px: 1800.0,
+        sz: 0.01,
+        cloid: None,
+        order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+    };
+    let response = exchange_client.order(order, None, None).await.unwrap();
+    info!("Order placed: {response:?}");
+
+    // A per-call expiry takes precedence over the client's. This one has already passed, so the
+    // exchange rejects the order
+    let order = ClientOrderRequest {
+        asset: "ETH".to_string(),
+        is_buy: true,
+        reduce_only: false,
+        limit_px: 1800.0,
+        sz: 0.01,
+        cloid: None,
+        order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+    };
+    let options = ActionOptions {
+        expires_after: Some(now_ms() - 1_000),
+        ..ActionOptions::default()
+    };
+    let response = exchange_client
+        .order(order, Some(options), None)
+        .await
+        .unwrap();
+    info!("Expired order response: {response:?}");
+}
@@ -25,6 +25,7 @@ async fn main() {
     spot_meta_and_asset_contexts_example(&info_client).await;
     query_order_by_oid_example(&info_client).await;
     query_referral_state_example(&info_client).await;
+    assets_example(&info_client).await;
 }
 
 fn address() -> H160 {
@@ -174,3 +175,13 @@ async fn query_referral_state_example(info_client: &InfoClient) {
         info_client.query_referral_state(user).await.unwrap()
     );
 }
+
+async fn assets_example(info_client: &InfoClient) {
+    let assets = info_client.assets_for(["ETH", "@1"]).await.unwrap();
+    info!(
+        "ETH is asset {} with {} size decimals, @1 is {}",
+        assets.asset("ETH").unwrap(),
+        assets.sz_decimals("ETH").unwrap(),
+        assets.display_name("@1")
+    );
+}
@@ -12,19 +12,20 @@ async fn main() {
         .unwrap();
 
     let address = wallet.address();
-    let exchange_client = ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None)
-        .await
-        .unwrap();
+    let exchange_client =
+        ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None, None)
+            .await
+            .unwrap();
     let info_client = InfoClient::new(None, Some(BaseUrl::Testnet)).await.unwrap();
 
     let response = exchange_client
-        .update_leverage(5, "ETH", false, None)
+        .update_leverage(5, "ETH", false, None, None)
         .await
         .unwrap();
     info!("Update leverage response: {response:?}");
 
     let response = exchange_client
-        .update_isolated_margin(1.0, "ETH", None)
+        .update_isolated_margin(1.0, "ETH", None, None)
         .await
         .unwrap();
 
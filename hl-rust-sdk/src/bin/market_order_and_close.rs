@@ -0,0 +1,34 @@
+use ethers::signers::LocalWallet;
+use log::info;
+
+use hyperliquid_rust_sdk::{BaseUrl, ExchangeClient};
+use std::{thread::sleep, time::Duration};
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+        .parse()
+        .unwrap();
+
+    let exchange_client =
+        ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None, None)
+            .await
+            .unwrap();
+
+    let response = exchange_client
+        .market_open("ETH", true, 0.01, Some(0.01), None, None)
+        .await
+        .unwrap();
+    info!("Market order placed: {response:?}");
+
+    // So you can see the position before it's closed
+    sleep(Duration::from_secs(10));
+
+    let response = exchange_client
+        .market_close("ETH", None, Some(0.01), None, None, None)
+        .await
+        .unwrap();
+    info!("Market close placed: {response:?}");
+}
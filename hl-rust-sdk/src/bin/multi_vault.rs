@@ -0,0 +1,51 @@
+use ethers::{signers::LocalWallet, types::H160};
+use log::info;
+
+use hyperliquid_rust_sdk::{
+    ActionOptions, BaseUrl, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient, Tif,
+};
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+        .parse()
+        .unwrap();
+
+    // One client, and one fetch of meta, for all vaults led by the wallet
+    let exchange_client =
+        ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None, None)
+            .await
+            .unwrap();
+
+    let vaults: [H160; 2] = [
+        "0x1962905b0a2d0ce7907ae1a0d17f3e4a1f63dfb7"
+            .parse()
+            .unwrap(),
+        "0x1719884eb866cb12b2287399b15f7db5e7d775ea"
+            .parse()
+            .unwrap(),
+    ];
+
+    for vault_address in vaults {
+        let order = ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px: 1800.0,
+            sz: 0.01,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+        };
+        let options = ActionOptions {
+            vault_address: Some(vault_address),
+            ..ActionOptions::default()
+        };
+        let response = exchange_client
+            .order(order, Some(options), None)
+            .await
+            .unwrap();
+        info!("Order placed for vault {vault_address:?}: {response:?}");
+    }
+}
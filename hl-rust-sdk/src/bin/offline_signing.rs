@@ -0,0 +1,42 @@
+use ethers::signers::LocalWallet;
+use hyperliquid_rust_sdk::{
+    Actions, BaseUrl, ExchangeClient, ExchangePayload, ExchangePayloadBuilder,
+    ExchangeResponseStatus, ScheduleCancel,
+};
+use log::info;
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+        .parse()
+        .unwrap();
+
+    // On the signing machine: no network access needed
+    let payload = ExchangePayloadBuilder::new(&wallet, false)
+        .build(Actions::ScheduleCancel(ScheduleCancel { time: None }))
+        .await
+        .unwrap();
+    let serialized = serde_json::to_string(&payload).unwrap();
+    info!("Signed payload: {serialized}");
+
+    // On the broadcasting machine: the wallet passed here is not used to sign anything
+    let payload: ExchangePayload = serde_json::from_str(&serialized).unwrap();
+    let exchange_client = ExchangeClient::new(
+        None,
+        LocalWallet::new(&mut rand::thread_rng()),
+        Some(BaseUrl::Testnet),
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let response: ExchangeResponseStatus = exchange_client
+        .submit_signed_payload(&payload)
+        .await
+        .unwrap();
+    info!("Submit signed payload response: {response:?}");
+}
@@ -3,7 +3,7 @@ use log::info;
 
 use hyperliquid_rust_sdk::{
     BaseUrl, ClientCancelRequest, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient,
-    ExchangeDataStatus, ExchangeResponseStatus,
+    ExchangeDataStatus, ExchangeResponseStatus, Tif,
 };
 use std::{thread::sleep, time::Duration};
 
@@ -15,9 +15,10 @@ async fn main() {
         .parse()
         .unwrap();
 
-    let exchange_client = ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None)
-        .await
-        .unwrap();
+    let exchange_client =
+        ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None, None)
+            .await
+            .unwrap();
 
     let order = ClientOrderRequest {
         asset: "ETH".to_string(),
@@ -26,12 +27,10 @@ async fn main() {
         limit_px: 1800.0,
         sz: 0.01,
         cloid: None,
-        order_type: ClientOrder::Limit(ClientLimit {
-            tif: "Gtc".to_string(),
-        }),
+        order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
     };
 
-    let response = exchange_client.order(order, None).await.unwrap();
+    let response = exchange_client.order(order, None, None).await.unwrap();
     info!("Order placed: {response:?}");
 
     let response = match response {
@@ -54,6 +53,6 @@ async fn main() {
     };
 
     // This response will return an error if order was filled (since you can't cancel a filled order), otherwise it will cancel the order
-    let response = exchange_client.cancel(cancel, None).await.unwrap();
+    let response = exchange_client.cancel(cancel, None, None).await.unwrap();
     info!("Order potentially cancelled: {response:?}");
 }
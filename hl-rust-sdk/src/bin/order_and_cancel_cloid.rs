@@ -2,7 +2,8 @@ use ethers::signers::LocalWallet;
 use log::info;
 
 use hyperliquid_rust_sdk::{
-    BaseUrl, ClientCancelRequestCloid, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient,
+    BaseUrl, ClientCancelRequestCloid, ClientLimit, ClientOrder, ClientOrderRequest,
+    ExchangeClient, Tif,
 };
 use std::{thread::sleep, time::Duration};
 use uuid::Uuid;
@@ -15,9 +16,10 @@ async fn main() {
         .parse()
         .unwrap();
 
-    let exchange_client = ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None)
-        .await
-        .unwrap();
+    let exchange_client =
+        ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None, None)
+            .await
+            .unwrap();
 
     // Order and Cancel with cloid
     let cloid = Uuid::new_v4();
@@ -28,12 +30,10 @@ async fn main() {
         limit_px: 1800.0,
         sz: 0.01,
         cloid: Some(cloid),
-        order_type: ClientOrder::Limit(ClientLimit {
-            tif: "Gtc".to_string(),
-        }),
+        order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
     };
 
-    let response = exchange_client.order(order, None).await.unwrap();
+    let response = exchange_client.order(order, None, None).await.unwrap();
     info!("Order placed: {response:?}");
 
     // So you can see the order before it's cancelled
@@ -45,6 +45,9 @@ async fn main() {
     };
 
     // This response will return an error if order was filled (since you can't cancel a filled order), otherwise it will cancel the order
-    let response = exchange_client.cancel_by_cloid(cancel, None).await.unwrap();
+    let response = exchange_client
+        .cancel_by_cloid(cancel, None, None)
+        .await
+        .unwrap();
     info!("Order potentially cancelled: {response:?}");
 }
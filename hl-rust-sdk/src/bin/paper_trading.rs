@@ -0,0 +1,61 @@
+/*
+Runs the market making example against a paper exchange fed with live testnet books, so that
+no order reaches the exchange. Fills and positions are only simulated.
+*/
+use ethers::signers::{LocalWallet, Signer};
+use std::sync::Arc;
+use tokio::{spawn, sync::mpsc::unbounded_channel};
+
+use hyperliquid_rust_sdk::{
+    BaseUrl, InfoClient, MarketMaker, MarketMakerInput, PaperExchange, Subscription,
+};
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+        .parse()
+        .unwrap();
+
+    let mut info_client = InfoClient::new(None, Some(BaseUrl::Testnet)).await.unwrap();
+    let paper_exchange = Arc::new(
+        PaperExchange::from_info_client(&info_client, &["ETH"])
+            .await
+            .unwrap(),
+    );
+    paper_exchange.deposit(wallet.address(), 10_000.0);
+
+    // Keep the books of the paper exchange up to date, which also fills resting orders
+    let (sender, mut receiver) = unbounded_channel();
+    info_client
+        .subscribe(
+            Subscription::L2Book {
+                coin: "ETH".to_string(),
+            },
+            sender,
+        )
+        .await
+        .unwrap();
+    let feed = paper_exchange.clone();
+    spawn(async move {
+        let _info_client = info_client;
+        while let Some(message) = receiver.recv().await {
+            feed.feed_message(&message).unwrap();
+        }
+    });
+
+    let market_maker_input = MarketMakerInput {
+        asset: "ETH".to_string(),
+        target_liquidity: 0.25,
+        max_bps_diff: 2,
+        half_spread: 1,
+        max_absolute_position_size: 0.5,
+        decimals: 1,
+        wallet,
+    };
+    MarketMaker::with_transport(market_maker_input, paper_exchange)
+        .await
+        .start()
+        .await
+}
@@ -0,0 +1,24 @@
+use hyperliquid_rust_sdk::{BaseUrl, InfoClient, RetryPolicy, WeightBudget};
+use log::info;
+use std::{sync::Arc, time::Duration};
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    let mut info_client = InfoClient::new(None, Some(BaseUrl::Testnet)).await.unwrap();
+
+    // Retry failed info requests up to 5 times, waiting at most 2 seconds between attempts
+    info_client.http_client.retry_policy = RetryPolicy {
+        max_retries: 5,
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_secs(2),
+    };
+    // Keep to a tenth of the exchange's limit, e.g. to leave room for other processes on the
+    // same IP address
+    info_client.http_client.weight_budget = Arc::new(WeightBudget::new(120));
+
+    for _ in 0..10 {
+        let all_mids = info_client.all_mids().await.unwrap();
+        info!("Fetched {} mids", all_mids.len());
+    }
+}
@@ -0,0 +1,37 @@
+use ethers::signers::LocalWallet;
+use log::info;
+use std::{sync::Arc, time::Duration};
+use tokio::time::sleep;
+
+use hyperliquid_rust_sdk::{BaseUrl, ExchangeClient};
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+        .parse()
+        .unwrap();
+
+    let exchange_client = Arc::new(
+        ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None, None)
+            .await
+            .unwrap(),
+    );
+
+    // Keep moving the deadline 60 seconds ahead, every 20 seconds, while the client is alive
+    let heartbeat = ExchangeClient::spawn_cancel_heartbeat(
+        &exchange_client,
+        Duration::from_secs(20),
+        Duration::from_secs(60),
+    );
+    sleep(Duration::from_secs(45)).await;
+
+    // Stop the heartbeat and remove the scheduled cancel
+    heartbeat.abort();
+    let response = exchange_client
+        .schedule_cancel(None, None, None)
+        .await
+        .unwrap();
+    info!("Schedule cancel response: {response:?}");
+}
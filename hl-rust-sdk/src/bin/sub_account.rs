@@ -0,0 +1,62 @@
+use ethers::signers::{LocalWallet, Signer};
+use log::info;
+
+use hyperliquid_rust_sdk::{
+    BaseUrl, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient, ExchangeResponseStatus,
+    InfoClient, Tif,
+};
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+        .parse()
+        .unwrap();
+    let user = wallet.address();
+
+    let exchange_client =
+        ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None, None)
+            .await
+            .unwrap();
+    let info_client = InfoClient::new(None, Some(BaseUrl::Testnet)).await.unwrap();
+
+    let response = exchange_client
+        .create_sub_account("example", None, None)
+        .await
+        .unwrap();
+    info!("Create sub-account response: {response:?}");
+    let sub_account_user = match response {
+        ExchangeResponseStatus::Ok(created) => created.sub_account_user,
+        ExchangeResponseStatus::Err(e) => panic!("error creating sub-account: {e}"),
+    };
+
+    let response = exchange_client
+        .sub_account_transfer(sub_account_user, true, 10.0, None, None)
+        .await
+        .unwrap();
+    info!("Sub-account transfer response: {response:?}");
+
+    let order = ClientOrderRequest {
+        asset: "ETH".to_string(),
+        is_buy: true,
+        reduce_only: false,
+        limit_px: 1800.0,
+        sz: 0.01,
+        cloid: None,
+        order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+    };
+    let response = exchange_client
+        .sub_account(sub_account_user)
+        .order(order, None, None)
+        .await
+        .unwrap();
+    info!("Sub-account order response: {response:?}");
+
+    for sub_account in info_client.sub_accounts(user).await.unwrap() {
+        info!(
+            "Sub-account {} ({:?}): {:?}",
+            sub_account.name, sub_account.sub_account_user, sub_account.clearinghouse_state
+        );
+    }
+}
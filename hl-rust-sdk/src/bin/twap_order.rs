@@ -0,0 +1,71 @@
+use ethers::signers::{LocalWallet, Signer};
+use log::info;
+
+use hyperliquid_rust_sdk::{
+    BaseUrl, ClientTwapRequest, ExchangeClient, ExchangeResponseStatus, InfoClient, Message,
+    Subscription, TwapStatus,
+};
+use tokio::{
+    spawn,
+    sync::mpsc::unbounded_channel,
+    time::{sleep, Duration},
+};
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+        .parse()
+        .unwrap();
+    let user = wallet.address();
+
+    let exchange_client =
+        ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None, None)
+            .await
+            .unwrap();
+    let mut info_client = InfoClient::new(None, Some(BaseUrl::Testnet)).await.unwrap();
+
+    let (sender, mut receiver) = unbounded_channel();
+    let subscription_id = info_client
+        .subscribe(Subscription::UserTwapSliceFills { user }, sender)
+        .await
+        .unwrap();
+
+    let twap = ClientTwapRequest {
+        asset: "ETH".to_string(),
+        is_buy: true,
+        reduce_only: false,
+        sz: 0.1,
+        minutes: 5,
+        randomize: false,
+    };
+    let response = exchange_client.twap_order(twap, None, None).await.unwrap();
+    info!("TWAP order response: {response:?}");
+
+    let twap_id = match response {
+        ExchangeResponseStatus::Ok(twap) => match twap.data.status {
+            TwapStatus::Running(running) => running.twap_id,
+            status => panic!("TWAP order not running: {status:?}"),
+        },
+        ExchangeResponseStatus::Err(e) => panic!("error with exchange response: {e}"),
+    };
+
+    spawn(async move {
+        sleep(Duration::from_secs(90)).await;
+        let response = exchange_client
+            .twap_cancel("ETH", twap_id, None, None)
+            .await
+            .unwrap();
+        info!("TWAP cancel response: {response:?}");
+
+        let fills = info_client.user_twap_slice_fills(user).await.unwrap();
+        info!("TWAP slice fills: {fills:?}");
+        info_client.unsubscribe(subscription_id).await.unwrap()
+    });
+
+    // this loop ends when we unsubscribe
+    while let Some(Message::UserTwapSliceFills(twap_slice_fills)) = receiver.recv().await {
+        info!("Received TWAP slice fills: {twap_slice_fills:?}");
+    }
+}
@@ -1,5 +1,5 @@
 use ethers::signers::LocalWallet;
-use hyperliquid_rust_sdk::{BaseUrl, ExchangeClient};
+use hyperliquid_rust_sdk::{BaseUrl, ExchangeClient, ARBITRUM_ONE_CHAIN_ID};
 use log::info;
 
 #[tokio::main]
@@ -10,9 +10,13 @@ async fn main() {
         .parse()
         .unwrap();
 
-    let exchange_client = ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None)
-        .await
-        .unwrap();
+    let mut exchange_client =
+        ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None, None)
+            .await
+            .unwrap();
+    // User signed actions default to the Arbitrum Sepolia chain id, some wallets only sign
+    // for the chain they're connected to
+    exchange_client.signature_chain_id = ARBITRUM_ONE_CHAIN_ID.into();
 
     let amount = "1"; // 1 USD
     let destination = "0x0D1d9635D0640821d15e323ac8AdADfA9c111414";
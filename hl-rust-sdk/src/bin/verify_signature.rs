@@ -0,0 +1,37 @@
+use ethers::signers::{LocalWallet, Signer};
+use hyperliquid_rust_sdk::{
+    recover_signer, verify_signer, Actions, BaseUrl, ExchangeClient, ExchangePayload,
+    ExchangePayloadBuilder, ScheduleCancel,
+};
+use log::info;
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+        .parse()
+        .unwrap();
+
+    // Audit a payload, e.g. one read from a request log
+    let payload = ExchangePayloadBuilder::new(&wallet, false)
+        .build(Actions::ScheduleCancel(ScheduleCancel { time: None }))
+        .await
+        .unwrap();
+    let payload: ExchangePayload =
+        serde_json::from_str(&serde_json::to_string(&payload).unwrap()).unwrap();
+
+    let signer = recover_signer(&payload, false).unwrap();
+    info!("Payload signed by {signer:?}");
+    verify_signer(&payload, false, wallet.address()).unwrap();
+
+    // Or have the client check every payload before sending it
+    let mut exchange_client =
+        ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None, None)
+            .await
+            .unwrap();
+    exchange_client.verify_signatures = true;
+
+    let response = exchange_client.schedule_cancel(None, None, None).await;
+    info!("Verified schedule cancel response: {response:?}");
+}
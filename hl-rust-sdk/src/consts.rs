@@ -2,4 +2,11 @@ pub static MAINNET_API_URL: &str = "https://api.hyperliquid.xyz";
 pub static TESTNET_API_URL: &str = "https://api.hyperliquid-testnet.xyz";
 pub static LOCAL_API_URL: &str = "http://localhost:3001";
 pub const EPSILON: f64 = 1e-9;
+pub const ARBITRUM_ONE_CHAIN_ID: u64 = 42161;
+pub const ARBITRUM_SEPOLIA_CHAIN_ID: u64 = 421614;
 pub(crate) const INF_BPS: u16 = 10_001;
+pub(crate) const DEFAULT_SLIPPAGE: f64 = 0.05;
+pub(crate) const MAX_SIGNIFICANT_FIGURES: i32 = 5;
+pub(crate) const MAX_DECIMALS_PERP: u32 = 6;
+pub(crate) const MAX_DECIMALS_SPOT: u32 = 8;
+pub(crate) const REQUEST_WEIGHT_PER_MINUTE: u32 = 1200;
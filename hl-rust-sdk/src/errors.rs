@@ -1,3 +1,5 @@
+use crate::ExchangeError;
+use ethers::types::H160;
 use reqwest::header::HeaderMap;
 use thiserror::Error;
 
@@ -9,7 +11,7 @@ pub enum Error {
         status_code: u16,
         error_code: Option<u16>,
         error_message: String,
-        headers: HeaderMap,
+        headers: Box<HeaderMap>,
         error_data: Option<String>,
     },
     #[error("Server error: status code: {status_code}, error message: {error_message}")]
@@ -19,6 +21,8 @@ pub enum Error {
     },
     #[error("Generic request error: {0:?}")]
     GenericRequest(String),
+    #[error("Connection error: {0:?}")]
+    Connection(String),
     #[error("Chain type not allowed for this function")]
     ChainNotAllowed,
     #[error("Asset not found")]
@@ -61,6 +65,22 @@ pub enum Error {
     NoCloid,
     #[error("ECDSA signature failed: {0:?}")]
     SignatureFailure(String),
+    #[error("Nonce store error: {0:?}")]
+    NonceStore(String),
     #[error("Vault address not found")]
     VaultAddressNotFound,
+    #[error("Invalid price {px} for {coin}")]
+    InvalidPrice { coin: String, px: f64 },
+    #[error("Invalid size {sz} for {coin}")]
+    InvalidSize { coin: String, sz: f64 },
+    #[error("Position not found")]
+    PositionNotFound,
+    #[error("Exchange error: {0}")]
+    Exchange(ExchangeError),
+    #[error("Unexpected exchange response: {0:?}")]
+    UnexpectedResponse(String),
+    #[error("Payload signed by {recovered:?} instead of {expected:?}")]
+    SignerMismatch { expected: H160, recovered: H160 },
+    #[error("Vault address {requested:?} requested for sub-account {expected:?}")]
+    VaultAddressMismatch { expected: H160, requested: H160 },
 }
@@ -1,4 +1,6 @@
-use crate::exchange::{cancel::CancelRequest, order::OrderRequest};
+use crate::exchange::{
+    cancel::CancelRequest, modify::ModifyRequest, order::OrderRequest, twap::TwapRequest,
+};
 pub(crate) use ethers::{
     abi::{encode, ParamType, Tokenizable},
     types::{
@@ -78,6 +80,54 @@ impl Eip712 for UsdSend {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ApproveBuilderFee {
+    pub signature_chain_id: U256,
+    pub hyperliquid_chain: String,
+    pub max_fee_rate: String,
+    pub builder: H160,
+    pub nonce: u64,
+}
+
+impl Eip712 for ApproveBuilderFee {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(eip_712_domain(self.signature_chain_id))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(eip712::make_type_hash(
+            format!("{HYPERLIQUID_EIP_PREFIX}ApproveBuilderFee"),
+            &[
+                ("hyperliquidChain".to_string(), ParamType::String),
+                ("maxFeeRate".to_string(), ParamType::String),
+                ("builder".to_string(), ParamType::Address),
+                ("nonce".to_string(), ParamType::Uint(64)),
+            ],
+        ))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let Self {
+            signature_chain_id: _,
+            hyperliquid_chain,
+            max_fee_rate,
+            builder,
+            nonce,
+        } = self;
+        let items = vec![
+            ethers::abi::Token::Uint(Self::type_hash()?.into()),
+            encode_eip712_type(hyperliquid_chain.clone().into_token()),
+            encode_eip712_type(max_fee_rate.clone().into_token()),
+            encode_eip712_type(builder.into_token()),
+            encode_eip712_type(nonce.into_token()),
+        ];
+        Ok(keccak256(encode(&items)))
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateLeverage {
@@ -98,7 +148,77 @@ pub struct UpdateIsolatedMargin {
 #[serde(rename_all = "camelCase")]
 pub struct BulkOrder {
     pub orders: Vec<OrderRequest>,
-    pub grouping: String,
+    pub grouping: Grouping,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub builder: Option<BuilderInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct BuilderInfo {
+    #[serde(rename = "b", alias = "builder")]
+    pub builder: H160,
+    /// Fee charged on top of the exchange fees, in tenths of a basis point
+    #[serde(rename = "f", alias = "fee")]
+    pub fee: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Grouping {
+    Na,
+    NormalTpsl,
+    PositionTpsl,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkModify {
+    pub modifies: Vec<ModifyRequest>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapOrder {
+    pub twap: TwapRequest,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapCancel {
+    #[serde(rename = "a", alias = "asset")]
+    pub asset: u32,
+    #[serde(rename = "t", alias = "twapId")]
+    pub twap_id: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleCancel {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSubAccount {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccountUsdTransfer {
+    pub sub_account_user: H160,
+    pub is_deposit: bool,
+    pub usd: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccountSpotTransfer {
+    pub sub_account_user: H160,
+    pub is_deposit: bool,
+    pub token: String,
+    pub amount: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -119,6 +239,7 @@ pub struct ApproveAgent {
     pub signature_chain_id: U256,
     pub hyperliquid_chain: String,
     pub agent_address: H160,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub agent_name: Option<String>,
     pub nonce: u64,
 }
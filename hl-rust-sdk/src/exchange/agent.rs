@@ -0,0 +1,21 @@
+use crate::{prelude::*, Error};
+use ethers::{signers::LocalWallet, types::H160};
+use serde::{Deserialize, Serialize};
+
+/// Key material for an agent (API wallet) approved by `ExchangeClient`, meant to be persisted
+/// and later turned back into a signing wallet.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentCredentials {
+    pub name: Option<String>,
+    pub address: H160,
+    pub private_key: String,
+}
+
+impl AgentCredentials {
+    pub fn wallet(&self) -> Result<LocalWallet> {
+        self.private_key
+            .parse::<LocalWallet>()
+            .map_err(|e| Error::PrivateKeyParse(e.to_string()))
+    }
+}
@@ -0,0 +1,480 @@
+//! Order requests and transfers with exact `Decimal` prices, sizes and amounts, which are written
+//! to the wire without going through `f64`
+
+use crate::{
+    consts::DEFAULT_SLIPPAGE,
+    exchange::{
+        actions::{BulkModify, BulkOrder, Grouping, TwapOrder, UpdateIsolatedMargin},
+        modify::{ModifyRequest, OidOrCloid},
+        order::{Limit, Order, OrderRequest, Trigger},
+        twap::TwapRequest,
+        ActionOptions, ClientLimit, ExchangeClient, ExchangeResponseStatus, NormalizationMode,
+        OrderNormalizer, Tif, TpSl, TwapResponse,
+    },
+    helpers::uuid_to_hex_string,
+    number::{decimal_to_wire, parse_decimal},
+    prelude::*,
+    Actions, ClassTransfer, Error, HyperliquidSigner, SpotUser,
+};
+use rust_decimal::{
+    prelude::{FromPrimitive, ToPrimitive},
+    Decimal, RoundingStrategy,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub struct ClientTriggerDecimal {
+    pub is_market: bool,
+    pub trigger_px: Decimal,
+    pub tpsl: TpSl,
+}
+
+pub enum ClientOrderDecimal {
+    Limit(ClientLimit),
+    Trigger(ClientTriggerDecimal),
+}
+
+/// `ClientOrderRequest` with an exact price and size
+pub struct ClientOrderRequestDecimal {
+    pub asset: String,
+    pub is_buy: bool,
+    pub reduce_only: bool,
+    pub limit_px: Decimal,
+    pub sz: Decimal,
+    pub cloid: Option<Uuid>,
+    pub order_type: ClientOrderDecimal,
+}
+
+impl ClientOrderRequestDecimal {
+    pub(crate) fn convert(self, coin_to_asset: &HashMap<String, u32>) -> Result<OrderRequest> {
+        let order_type = match self.order_type {
+            ClientOrderDecimal::Limit(limit) => Order::Limit(Limit { tif: limit.tif }),
+            ClientOrderDecimal::Trigger(trigger) => Order::Trigger(Trigger {
+                trigger_px: decimal_to_wire(trigger.trigger_px),
+                is_market: trigger.is_market,
+                tpsl: trigger.tpsl,
+            }),
+        };
+        let &asset = coin_to_asset.get(&self.asset).ok_or(Error::AssetNotFound)?;
+
+        Ok(OrderRequest {
+            asset,
+            is_buy: self.is_buy,
+            reduce_only: self.reduce_only,
+            limit_px: decimal_to_wire(self.limit_px),
+            sz: decimal_to_wire(self.sz),
+            order_type,
+            cloid: self.cloid.map(uuid_to_hex_string),
+        })
+    }
+}
+
+/// `ClientTwapRequest` with an exact size
+pub struct ClientTwapRequestDecimal {
+    pub asset: String,
+    pub is_buy: bool,
+    pub reduce_only: bool,
+    pub sz: Decimal,
+    pub minutes: u32,
+    pub randomize: bool,
+}
+
+impl ClientTwapRequestDecimal {
+    pub(crate) fn convert(self, coin_to_asset: &HashMap<String, u32>) -> Result<TwapRequest> {
+        let &asset = coin_to_asset.get(&self.asset).ok_or(Error::AssetNotFound)?;
+
+        Ok(TwapRequest {
+            asset,
+            is_buy: self.is_buy,
+            sz: decimal_to_wire(self.sz),
+            reduce_only: self.reduce_only,
+            minutes: self.minutes,
+            randomize: self.randomize,
+        })
+    }
+}
+
+/// Power of ten of the leading digit of `x`, or None for zero
+fn magnitude(x: Decimal) -> Option<i32> {
+    let digits = x.mantissa().unsigned_abs().checked_ilog10()?;
+    Some(digits as i32 - x.scale() as i32)
+}
+
+/// USDC amount in the integer micro-units that transfers and margin updates are sent in
+fn to_micro_usdc(usdc: Decimal) -> Result<i64> {
+    (usdc * Decimal::from(1_000_000))
+        .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+        .to_i64()
+        .ok_or_else(|| Error::GenericParse(format!("USDC amount out of range: {usdc}")))
+}
+
+impl OrderNormalizer {
+    pub fn round_px_decimal(&self, coin: &str, px: Decimal) -> Result<Decimal> {
+        let decimals = self.px_decimals_of_magnitude(coin, magnitude(px))?;
+        Ok(px.round_dp_with_strategy(decimals, RoundingStrategy::MidpointAwayFromZero))
+    }
+
+    /// Rounds the price and moves it by `ticks` valid price increments
+    pub fn offset_px_decimal(&self, coin: &str, px: Decimal, ticks: i32) -> Result<Decimal> {
+        let px = self.round_px_decimal(coin, px)?;
+        let tick_size = Decimal::new(1, self.px_decimals_of_magnitude(coin, magnitude(px))?);
+        self.round_px_decimal(coin, px + Decimal::from(ticks) * tick_size)
+    }
+
+    pub fn round_sz_decimal(&self, coin: &str, sz: Decimal) -> Result<Decimal> {
+        Ok(sz.round_dp_with_strategy(
+            self.sz_decimals(coin)?,
+            RoundingStrategy::ToNegativeInfinity,
+        ))
+    }
+
+    pub fn normalize_px_decimal(
+        &self,
+        coin: &str,
+        px: Decimal,
+        mode: NormalizationMode,
+    ) -> Result<Decimal> {
+        let rounded = self.round_px_decimal(coin, px)?;
+        if mode == NormalizationMode::Strict && rounded != px {
+            return Err(Error::InvalidPrice {
+                coin: coin.to_string(),
+                px: px.to_f64().unwrap_or(f64::NAN),
+            });
+        }
+        Ok(rounded)
+    }
+
+    pub fn normalize_sz_decimal(
+        &self,
+        coin: &str,
+        sz: Decimal,
+        mode: NormalizationMode,
+    ) -> Result<Decimal> {
+        let rounded = self.round_sz_decimal(coin, sz)?;
+        if mode == NormalizationMode::Strict && rounded != sz {
+            return Err(Error::InvalidSize {
+                coin: coin.to_string(),
+                sz: sz.to_f64().unwrap_or(f64::NAN),
+            });
+        }
+        Ok(rounded)
+    }
+
+    pub fn normalize_order_decimal(
+        &self,
+        order: ClientOrderRequestDecimal,
+        mode: NormalizationMode,
+    ) -> Result<ClientOrderRequestDecimal> {
+        let order_type = match order.order_type {
+            ClientOrderDecimal::Trigger(trigger) => {
+                ClientOrderDecimal::Trigger(ClientTriggerDecimal {
+                    trigger_px: self.normalize_px_decimal(
+                        &order.asset,
+                        trigger.trigger_px,
+                        mode,
+                    )?,
+                    ..trigger
+                })
+            }
+            limit => limit,
+        };
+
+        Ok(ClientOrderRequestDecimal {
+            limit_px: self.normalize_px_decimal(&order.asset, order.limit_px, mode)?,
+            sz: self.normalize_sz_decimal(&order.asset, order.sz, mode)?,
+            order_type,
+            ..order
+        })
+    }
+}
+
+impl<S: HyperliquidSigner> ExchangeClient<S> {
+    pub async fn order_decimal(
+        &self,
+        order: ClientOrderRequestDecimal,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.bulk_order_decimal(vec![order], options, wallet).await
+    }
+
+    pub async fn bulk_order_decimal(
+        &self,
+        orders: Vec<ClientOrderRequestDecimal>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let assets = self
+            .info_client
+            .assets_for(orders.iter().map(|order| order.asset.as_str()))
+            .await?;
+
+        let mut transformed_orders = Vec::new();
+        for order in orders {
+            let order = match self.normalization {
+                Some(mode) => assets.normalizer.normalize_order_decimal(order, mode)?,
+                None => order,
+            };
+            transformed_orders.push(order.convert(&assets.coin_to_asset)?);
+        }
+
+        let action = Actions::Order(BulkOrder {
+            orders: transformed_orders,
+            grouping: Grouping::Na,
+            builder: None,
+        });
+        self.post_l1_action(action, wallet, self.vault_for(options), options)
+            .await
+    }
+
+    pub async fn modify_order_decimal(
+        &self,
+        oid: u64,
+        order: ClientOrderRequestDecimal,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let assets = self.info_client.assets_for([order.asset.as_str()]).await?;
+
+        let order = match self.normalization {
+            Some(mode) => assets.normalizer.normalize_order_decimal(order, mode)?,
+            None => order,
+        };
+        let action = Actions::BatchModify(BulkModify {
+            modifies: vec![ModifyRequest {
+                oid: OidOrCloid::Oid(oid),
+                order: order.convert(&assets.coin_to_asset)?,
+            }],
+        });
+        self.post_l1_action(action, wallet, self.vault_for(options), options)
+            .await
+    }
+
+    pub async fn market_open_decimal(
+        &self,
+        coin: &str,
+        is_buy: bool,
+        sz: Decimal,
+        slippage: Option<f64>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let slippage = Decimal::from_f64(slippage.unwrap_or(DEFAULT_SLIPPAGE))
+            .ok_or_else(|| Error::GenericParse("slippage is not a number".to_string()))?;
+
+        let quotes = self.mid_quotes(coin).await?;
+        let mid = quotes
+            .iter()
+            .map(|px| parse_decimal(px))
+            .sum::<Result<Decimal>>()?
+            / Decimal::from(quotes.len());
+        let px = if is_buy {
+            mid * (Decimal::ONE + slippage)
+        } else {
+            mid * (Decimal::ONE - slippage)
+        };
+        let limit_px = self
+            .info_client
+            .assets_for([coin])
+            .await?
+            .normalizer
+            .round_px_decimal(coin, px)?;
+
+        let order = ClientOrderRequestDecimal {
+            asset: coin.to_string(),
+            is_buy,
+            reduce_only: false,
+            limit_px,
+            sz,
+            cloid: None,
+            order_type: ClientOrderDecimal::Limit(ClientLimit { tif: Tif::Ioc }),
+        };
+
+        self.order_decimal(order, options, wallet).await
+    }
+
+    pub async fn twap_order_decimal(
+        &self,
+        twap: ClientTwapRequestDecimal,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus<TwapResponse>> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let assets = self.info_client.assets_for([twap.asset.as_str()]).await?;
+
+        let twap = match self.normalization {
+            Some(mode) => ClientTwapRequestDecimal {
+                sz: assets
+                    .normalizer
+                    .normalize_sz_decimal(&twap.asset, twap.sz, mode)?,
+                ..twap
+            },
+            None => twap,
+        };
+        let action = Actions::TwapOrder(TwapOrder {
+            twap: twap.convert(&assets.coin_to_asset)?,
+        });
+        self.post_l1_action(action, wallet, self.vault_for(options), options)
+            .await
+    }
+
+    pub async fn class_transfer_decimal(
+        &self,
+        usdc: Decimal,
+        to_perp: bool,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let usdc = to_micro_usdc(usdc)?
+            .try_into()
+            .map_err(|_| Error::GenericParse(format!("negative USDC amount: {usdc}")))?;
+        let wallet = wallet.unwrap_or(&self.wallet);
+
+        let action = Actions::SpotUser(SpotUser {
+            class_transfer: ClassTransfer { usdc, to_perp },
+        });
+        self.post_l1_action(action, wallet, self.vault_for(options), options)
+            .await
+    }
+
+    pub async fn update_isolated_margin_decimal(
+        &self,
+        amount: Decimal,
+        coin: &str,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+
+        let asset_index = self.info_client.assets_for([coin]).await?.asset(coin)?;
+        let action = Actions::UpdateIsolatedMargin(UpdateIsolatedMargin {
+            asset: asset_index,
+            is_buy: true,
+            ntli: to_micro_usdc(amount)?,
+        });
+        self.post_l1_action(action, wallet, self.vault_for(options), options)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{transport::tests::mock_api, AssetMeta, BaseUrl, Meta, SpotMeta};
+    use ethers::signers::LocalWallet;
+    use serde_json::json;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_normalize_decimal() -> Result<()> {
+        let meta = Meta {
+            universe: vec![
+                AssetMeta {
+                    name: "BTC".to_string(),
+                    sz_decimals: 5,
+                },
+                AssetMeta {
+                    name: "DOGE".to_string(),
+                    sz_decimals: 0,
+                },
+            ],
+        };
+        let spot_meta = SpotMeta {
+            universe: vec![],
+            tokens: vec![],
+        };
+        let normalizer = OrderNormalizer::new(&meta, &spot_meta);
+        let lenient = NormalizationMode::Lenient;
+        let strict = NormalizationMode::Strict;
+
+        assert_eq!(
+            normalizer.round_px_decimal("BTC", dec("65432.5"))?,
+            dec("65433")
+        );
+        assert_eq!(
+            normalizer.round_px_decimal("BTC", dec("100000.4"))?,
+            dec("100000")
+        );
+        assert_eq!(
+            normalizer.round_px_decimal("DOGE", dec("0.1234567"))?,
+            dec("0.12346")
+        );
+        assert_eq!(
+            normalizer.offset_px_decimal("DOGE", dec("0.12346"), -1)?,
+            dec("0.12345")
+        );
+        assert_eq!(
+            normalizer.offset_px_decimal("BTC", dec("99999.4"), 1)?,
+            dec("100000")
+        );
+        assert!(normalizer
+            .normalize_px_decimal("BTC", dec("65432.1"), strict)
+            .is_err());
+        assert_eq!(
+            normalizer.normalize_px_decimal("DOGE", dec("0.12346"), strict)?,
+            dec("0.12346")
+        );
+
+        assert_eq!(
+            normalizer.normalize_sz_decimal("BTC", dec("0.123456"), lenient)?,
+            dec("0.12345")
+        );
+        assert!(normalizer
+            .normalize_sz_decimal("BTC", dec("0.123456"), strict)
+            .is_err());
+        assert_eq!(
+            normalizer.normalize_sz_decimal("DOGE", dec("10.9"), lenient)?,
+            dec("10")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_decimal_requests_on_the_wire() -> Result<()> {
+        let transport = mock_api();
+        let wallet: LocalWallet =
+            "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+                .parse()
+                .map_err(|e: ethers::signers::WalletError| Error::Wallet(e.to_string()))?;
+        let mut exchange_client = ExchangeClient::with_transport(
+            transport.clone(),
+            wallet,
+            Some(BaseUrl::Testnet),
+            None,
+            None,
+            None,
+        )
+        .await?;
+        exchange_client.normalization = Some(NormalizationMode::Lenient);
+        let last_action = || transport.requests().last().unwrap().1["action"].clone();
+
+        let order = ClientOrderRequestDecimal {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px: dec("1800.10"),
+            sz: dec("0.30009"),
+            cloid: None,
+            order_type: ClientOrderDecimal::Limit(ClientLimit { tif: Tif::Gtc }),
+        };
+        exchange_client.order_decimal(order, None, None).await?;
+        let action = last_action();
+        assert_eq!(action["orders"][0]["p"], json!("1800.1"));
+        assert_eq!(action["orders"][0]["s"], json!("0.3"));
+
+        exchange_client
+            .class_transfer_decimal(dec("12.3456785"), true, None, None)
+            .await?;
+        assert_eq!(last_action()["classTransfer"]["usdc"], json!(12345679));
+        let res = exchange_client
+            .class_transfer_decimal(dec("-1"), true, None, None)
+            .await;
+        assert!(matches!(res, Err(Error::GenericParse(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use ethers::types::H160;
+use serde_json::{json, Value};
+
+/// Order and TWAP ids handed out in dry runs, far above the ones the exchange assigns
+static NEXT_DRY_RUN_ID: AtomicU64 = AtomicU64::new(1 << 62);
+
+fn next_id() -> u64 {
+    NEXT_DRY_RUN_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Response the exchange would send if it accepted `action`. Orders rest with a made up oid,
+/// cancels and modifies succeed, and anything else gets the plain `default` response.
+pub(crate) fn dry_run_response(action: &Value) -> Value {
+    let count = |key: &str| action[key].as_array().map_or(1, Vec::len);
+    let response = match action["type"].as_str().unwrap_or_default() {
+        "order" => {
+            let statuses: Vec<Value> = (0..count("orders"))
+                .map(|_| json!({"resting": {"oid": next_id()}}))
+                .collect();
+            json!({"type": "order", "data": {"statuses": statuses}})
+        }
+        response_type @ ("cancel" | "cancelByCloid") => {
+            let statuses = vec!["success"; count("cancels")];
+            json!({"type": response_type, "data": {"statuses": statuses}})
+        }
+        response_type @ ("batchModify" | "modify") => {
+            let statuses = vec!["success"; count("modifies")];
+            json!({"type": response_type, "data": {"statuses": statuses}})
+        }
+        "twapOrder" => {
+            json!({"type": "twapOrder", "data": {"status": {"running": {"twapId": next_id()}}}})
+        }
+        "twapCancel" => json!({"type": "twapCancel", "data": {"status": "success"}}),
+        "createSubAccount" => {
+            json!({"type": "createSubAccount", "data": H160::from_low_u64_be(next_id())})
+        }
+        _ => json!({"type": "default"}),
+    };
+    json!({"status": "ok", "response": response})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::*, CreateSubAccountResponse, Error, ExchangeDataStatus, ExchangeResponse,
+        ExchangeResponseStatus, TwapResponse, TwapStatus,
+    };
+    use serde::Deserialize;
+
+    fn parse<T: for<'a> Deserialize<'a>>(action: Value) -> Result<T> {
+        match serde_json::from_value(dry_run_response(&action))
+            .map_err(|e| Error::JsonParse(e.to_string()))?
+        {
+            ExchangeResponseStatus::Ok(response) => Ok(response),
+            ExchangeResponseStatus::Err(e) => Err(Error::Exchange(e)),
+        }
+    }
+
+    #[test]
+    fn test_dry_run_responses() -> Result<()> {
+        let response: ExchangeResponse = parse(json!({
+            "type": "order",
+            "orders": [{"a": 0}, {"a": 1}],
+            "grouping": "na"
+        }))?;
+        let statuses = response.data.map(|data| data.statuses).unwrap_or_default();
+        assert!(matches!(
+            &statuses[..],
+            [ExchangeDataStatus::Resting(first), ExchangeDataStatus::Resting(second)]
+                if first.oid != second.oid
+        ));
+
+        let response: ExchangeResponse =
+            parse(json!({"type": "cancel", "cancels": [{"a": 0, "o": 1}]}))?;
+        let statuses = response.data.map(|data| data.statuses).unwrap_or_default();
+        assert!(matches!(&statuses[..], [ExchangeDataStatus::Success]));
+
+        let response: TwapResponse = parse(json!({"type": "twapOrder"}))?;
+        assert!(matches!(response.data.status, TwapStatus::Running(_)));
+
+        let response: CreateSubAccountResponse = parse(json!({"type": "createSubAccount"}))?;
+        assert!(!response.sub_account_user.is_zero());
+
+        let response: ExchangeResponse = parse(json!({"type": "updateLeverage"}))?;
+        assert_eq!(response.response_type, "default");
+        assert!(response.data.is_none());
+        Ok(())
+    }
+}
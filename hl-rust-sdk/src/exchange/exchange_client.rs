@@ -1,48 +1,86 @@
 use crate::signature::sign_typed_data;
 use crate::{
+    consts::{ARBITRUM_SEPOLIA_CHAIN_ID, DEFAULT_SLIPPAGE},
     exchange::{
         actions::{
-            ApproveAgent, BulkCancel, BulkOrder, UpdateIsolatedMargin, UpdateLeverage, UsdSend,
+            ApproveAgent, ApproveBuilderFee, BuilderInfo, BulkCancel, BulkModify, BulkOrder,
+            CreateSubAccount, Grouping, ScheduleCancel, SubAccountSpotTransfer,
+            SubAccountUsdTransfer, TwapCancel, TwapOrder, UpdateIsolatedMargin, UpdateLeverage,
+            UsdSend,
         },
         cancel::{CancelRequest, CancelRequestCloid},
-        ClientCancelRequest, ClientOrderRequest,
+        dry_run::dry_run_response,
+        modify::{ModifyRequest, OidOrCloid},
+        ClientCancelRequest, ClientModifyRequest, ClientModifyRequestCloid, ClientOrderRequest,
+        ClientTwapRequest,
     },
-    helpers::{generate_random_key, next_nonce, uuid_to_hex_string},
-    info::info_client::InfoClient,
+    helpers::{generate_random_key, now_timestamp_ms, uuid_to_hex_string},
+    info::{info_client::InfoClient, AssetRegistry, AssetSnapshot},
     meta::Meta,
+    nonce::shared_nonce_manager,
+    number::parse_number,
     prelude::*,
     req::HttpClient,
-    signature::sign_l1_action,
-    BaseUrl, BulkCancelCloid, Error, ExchangeResponseStatus,
+    signature::{sign_l1_action, verify_signer},
+    transport::{ReqwestTransport, Transport},
+    AgentCredentials, BaseUrl, BracketOrderStatuses, BulkCancelCloid, ClientLimit, ClientOrder,
+    ClientTrigger, CreateSubAccountResponse, Error, ExchangePayload, ExchangeResponseStatus,
+    HyperliquidSigner, NonceManager, NormalizationMode, Tif, TpSl, TwapResponse,
 };
 use crate::{ClassTransfer, SpotSend, SpotUser, VaultTransfer, Withdraw3};
 use ethers::{
     abi::AbiEncode,
     signers::{LocalWallet, Signer},
-    types::{Signature, H160, H256},
+    types::{Signature, H160, H256, U256},
 };
-use log::debug;
+use log::{debug, error, info};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Weak},
+    time::Duration,
+};
+use tokio::{spawn, task::JoinHandle, time};
+use uuid::Uuid;
 
-use super::cancel::ClientCancelRequestCloid;
+use super::{cancel::ClientCancelRequestCloid, SubAccountClient};
 
-pub struct ExchangeClient {
+pub struct ExchangeClient<S: HyperliquidSigner = LocalWallet> {
     pub http_client: HttpClient,
-    pub wallet: LocalWallet,
-    pub meta: Meta,
+    pub info_client: InfoClient,
+    pub wallet: S,
+    /// Vault or sub-account that L1 actions are sent for, unless overridden per call with
+    /// `ActionOptions::vault_address`
     pub vault_address: Option<H160>,
-    pub coin_to_asset: HashMap<String, u32>,
+    pub normalization: Option<NormalizationMode>,
+    pub nonce_manager: Arc<dyn NonceManager>,
+    /// Time (ms since epoch) after which the exchange rejects L1 actions sent by this client
+    /// instead of executing them late
+    pub expires_after: Option<u64>,
+    /// EIP-712 domain chain id of user signed actions (transfers, withdrawals and approvals),
+    /// `ARBITRUM_SEPOLIA_CHAIN_ID` unless set otherwise
+    pub signature_chain_id: U256,
+    /// "Mainnet" or "Testnet" in user signed actions, from the base url unless set otherwise
+    pub hyperliquid_chain: String,
+    /// Recovers the signer of every payload before sending it and fails with
+    /// `Error::SignerMismatch` if it isn't the signing wallet, to catch misconfigured agent keys
+    /// and signers before the exchange rejects their actions
+    pub verify_signatures: bool,
+    /// Signs actions as usual but logs them instead of sending them, answering with the
+    /// response the exchange would send if it accepted them
+    pub dry_run: bool,
 }
 
-#[derive(Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct ExchangePayload {
-    action: serde_json::Value,
-    signature: Signature,
-    nonce: u64,
-    vault_address: Option<H160>,
+/// Per-call overrides of the client configuration for L1 actions
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ActionOptions {
+    /// Replaces `ExchangeClient::expires_after` for this action
+    pub expires_after: Option<u64>,
+    /// Vault or sub-account to trade for in place of `ExchangeClient::vault_address`. Ignored
+    /// by sub-account creation and transfers and vault transfers, which always act for the
+    /// signing wallet. `SubAccountClient` rejects any vault but its own sub-account.
+    pub vault_address: Option<H160>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -55,15 +93,29 @@ pub enum Actions {
     Order(BulkOrder),
     Cancel(BulkCancel),
     CancelByCloid(BulkCancelCloid),
+    ScheduleCancel(ScheduleCancel),
+    TwapOrder(TwapOrder),
+    TwapCancel(TwapCancel),
+    Modify(ModifyRequest),
+    BatchModify(BulkModify),
     ApproveAgent(ApproveAgent),
+    ApproveBuilderFee(ApproveBuilderFee),
     Withdraw3(Withdraw3),
     SpotUser(SpotUser),
     VaultTransfer(VaultTransfer),
+    CreateSubAccount(CreateSubAccount),
+    SubAccountTransfer(SubAccountUsdTransfer),
+    SubAccountSpotTransfer(SubAccountSpotTransfer),
     SpotSend(SpotSend),
 }
 
 impl Actions {
-    fn hash(&self, timestamp: u64, vault_address: Option<H160>) -> Result<H256> {
+    pub(crate) fn hash(
+        &self,
+        timestamp: u64,
+        vault_address: Option<H160>,
+        expires_after: Option<u64>,
+    ) -> Result<H256> {
         let mut bytes =
             rmp_serde::to_vec_named(self).map_err(|e| Error::RmpParse(e.to_string()))?;
         bytes.extend(timestamp.to_be_bytes());
@@ -73,125 +125,291 @@ impl Actions {
         } else {
             bytes.push(0);
         }
+        if let Some(expires_after) = expires_after {
+            bytes.push(0);
+            bytes.extend(expires_after.to_be_bytes());
+        }
         Ok(H256(ethers::utils::keccak256(bytes)))
     }
+
+    /// Request weight counted by the exchange towards the rate limit
+    pub fn weight(&self) -> u32 {
+        let batch_len = match self {
+            Actions::Order(bulk_order) => bulk_order.orders.len(),
+            Actions::Cancel(bulk_cancel) => bulk_cancel.cancels.len(),
+            Actions::CancelByCloid(bulk_cancel) => bulk_cancel.cancels.len(),
+            Actions::BatchModify(bulk_modify) => bulk_modify.modifies.len(),
+            _ => 1,
+        };
+        1 + batch_len as u32 / 40
+    }
+
+    /// Whether the action may be sent again after a request that got no answer.
+    ///
+    /// Retries resend the same signed payload, nonce included. The exchange keeps the nonces it
+    /// has seen for each signer and rejects a payload whose nonce was already used, so a resent
+    /// action is never applied twice. If the first attempt went through, the retry is answered
+    /// with that rejection instead of the result of the action, which only matters if the caller
+    /// needs that result. It doesn't for cancels, leverage updates, TWAP cancels and scheduled
+    /// cancels, whose outcome can be read from the open orders and positions, nor for orders
+    /// which all have a cloid, whose status can be looked up by cloid. Other actions, e.g.
+    /// transfers and orders without cloids, fail instead so that the caller checks the account
+    /// before acting again.
+    pub fn is_idempotent(&self) -> bool {
+        match self {
+            Actions::Order(bulk_order) => bulk_order.orders.iter().all(|o| o.cloid.is_some()),
+            Actions::Cancel(_)
+            | Actions::CancelByCloid(_)
+            | Actions::ScheduleCancel(_)
+            | Actions::UpdateLeverage(_)
+            | Actions::TwapCancel(_) => true,
+            _ => false,
+        }
+    }
 }
 
-impl ExchangeClient {
+impl<S: HyperliquidSigner> ExchangeClient<S> {
     pub async fn new(
         client: Option<Client>,
-        wallet: LocalWallet,
+        wallet: S,
         base_url: Option<BaseUrl>,
         meta: Option<Meta>,
         vault_address: Option<H160>,
-    ) -> Result<ExchangeClient> {
+        nonce_manager: Option<Arc<dyn NonceManager>>,
+    ) -> Result<ExchangeClient<S>> {
         let client = client.unwrap_or_default();
         let base_url = base_url.unwrap_or(BaseUrl::Mainnet);
+        let transport = Arc::new(ReqwestTransport::new(client, base_url.get_url()));
+        ExchangeClient::with_transport(
+            transport,
+            wallet,
+            Some(base_url),
+            meta,
+            vault_address,
+            nonce_manager,
+        )
+        .await
+    }
 
-        let info = InfoClient::new(None, Some(base_url)).await?;
-        let meta = if let Some(meta) = meta {
-            meta
+    /// Sends requests, including the ones fetching metadata, through `transport` rather than
+    /// over HTTP, e.g. to a `MockTransport` in tests
+    pub async fn with_transport(
+        transport: Arc<dyn Transport>,
+        wallet: S,
+        base_url: Option<BaseUrl>,
+        meta: Option<Meta>,
+        vault_address: Option<H160>,
+        nonce_manager: Option<Arc<dyn NonceManager>>,
+    ) -> Result<ExchangeClient<S>> {
+        let base_url = base_url.unwrap_or(BaseUrl::Mainnet);
+        let hyperliquid_chain = base_url.hyperliquid_chain().to_string();
+
+        let info_client =
+            InfoClient::with_transport(transport.clone(), Some(base_url.clone())).await?;
+        if let Some(meta) = meta {
+            let spot_meta = info_client.spot_meta().await?;
+            info_client
+                .asset_registry
+                .update(AssetSnapshot::new(meta, spot_meta));
         } else {
-            info.meta().await?
-        };
-
-        let mut coin_to_asset = HashMap::new();
-        for (asset_ind, asset) in meta.universe.iter().enumerate() {
-            coin_to_asset.insert(asset.name.clone(), asset_ind as u32);
+            info_client.refresh_assets().await?;
         }
-
-        coin_to_asset = info
-            .spot_meta()
-            .await?
-            .add_pair_and_name_to_index_map(coin_to_asset);
+        let http_client = HttpClient {
+            retry_policy: info_client.http_client.retry_policy.clone(),
+            weight_budget: info_client.http_client.weight_budget.clone(),
+            ..HttpClient::new(transport, base_url)
+        };
 
         Ok(ExchangeClient {
+            info_client,
             wallet,
-            meta,
             vault_address,
-            http_client: HttpClient {
-                client,
-                base_url: base_url.get_url(),
-            },
-            coin_to_asset,
+            http_client,
+            normalization: None,
+            nonce_manager: nonce_manager.unwrap_or_else(shared_nonce_manager),
+            expires_after: None,
+            signature_chain_id: ARBITRUM_SEPOLIA_CHAIN_ID.into(),
+            hyperliquid_chain,
+            verify_signatures: false,
+            dry_run: false,
         })
     }
 
-    async fn post(
+    /// Looks assets up in `asset_registry`, e.g. the one of an `InfoClient` refreshing it with
+    /// `InfoClient::spawn_asset_refresh`
+    pub fn with_asset_registry(mut self, asset_registry: Arc<AssetRegistry>) -> ExchangeClient<S> {
+        self.info_client = self.info_client.with_asset_registry(asset_registry);
+        self
+    }
+
+    /// Asset ids, names and decimals, shared with and refreshed by `info_client`
+    pub async fn assets(&self) -> Result<Arc<AssetSnapshot>> {
+        self.info_client.assets().await
+    }
+
+    /// Perp metadata of the current asset snapshot, which may be stale
+    #[deprecated(note = "use `assets().await?.meta`, which is refreshed when assets are listed")]
+    pub fn meta(&self) -> Meta {
+        self.info_client.asset_registry.snapshot().meta.clone()
+    }
+
+    /// Asset ids of the current asset snapshot, which may be stale
+    #[deprecated(
+        note = "use `assets().await?.coin_to_asset`, which is refreshed when assets are listed"
+    )]
+    pub fn coin_to_asset(&self) -> HashMap<String, u32> {
+        self.info_client
+            .asset_registry
+            .snapshot()
+            .coin_to_asset
+            .clone()
+    }
+
+    fn normalize(
+        &self,
+        order: ClientOrderRequest,
+        assets: &AssetSnapshot,
+    ) -> Result<ClientOrderRequest> {
+        match self.normalization {
+            Some(mode) => assets.normalizer.normalize_order(order, mode),
+            None => Ok(order),
+        }
+    }
+
+    async fn post<T: for<'a> Deserialize<'a>>(
         &self,
         action: serde_json::Value,
         signature: Signature,
         nonce: u64,
-    ) -> Result<ExchangeResponseStatus> {
-        let exchange_payload = ExchangePayload {
+        vault_address: Option<H160>,
+        signer: H160,
+    ) -> Result<ExchangeResponseStatus<T>> {
+        let payload = ExchangePayload {
             action,
             signature,
             nonce,
-            vault_address: self.vault_address,
+            vault_address,
+            expires_after: None,
         };
-        let res = serde_json::to_string(&exchange_payload)
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        self.verify_and_submit(&payload, signer).await
+    }
+
+    async fn verify_and_submit<T: for<'a> Deserialize<'a>>(
+        &self,
+        payload: &ExchangePayload,
+        signer: H160,
+    ) -> Result<ExchangeResponseStatus<T>> {
+        if self.verify_signatures {
+            verify_signer(payload, self.http_client.is_mainnet(), signer)?;
+        }
+        self.submit_signed_payload(payload).await
+    }
+
+    /// Vault or sub-account that an L1 action with `options` is sent on behalf of
+    pub(super) fn vault_for(&self, options: Option<ActionOptions>) -> Option<H160> {
+        options
+            .and_then(|options| options.vault_address)
+            .or(self.vault_address)
+    }
+
+    /// Signs an L1 action with the next nonce of `wallet` and sends it
+    pub(super) async fn post_l1_action<T: for<'a> Deserialize<'a>>(
+        &self,
+        action: Actions,
+        wallet: &S,
+        vault_address: Option<H160>,
+        options: Option<ActionOptions>,
+    ) -> Result<ExchangeResponseStatus<T>> {
+        let options = options.unwrap_or_default();
+        let expires_after = options.expires_after.or(self.expires_after);
+        let nonce = self.nonce_manager.next_nonce(wallet.address())?;
+
+        let connection_id = action.hash(nonce, vault_address, expires_after)?;
+        let is_mainnet = self.http_client.is_mainnet();
+        let signature = sign_l1_action(wallet, connection_id, is_mainnet).await?;
+
+        let payload = ExchangePayload {
+            action: serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+            signature,
+            nonce,
+            vault_address,
+            expires_after,
+        };
+        self.verify_and_submit(&payload, wallet.address()).await
+    }
+
+    /// Sends a payload signed ahead of time, e.g. by an `ExchangePayloadBuilder` on another
+    /// machine. Use `ExchangeResponseStatus<TwapResponse>` and the like for actions that
+    /// respond with more than statuses.
+    pub async fn submit_signed_payload<T: for<'a> Deserialize<'a>>(
+        &self,
+        payload: &ExchangePayload,
+    ) -> Result<ExchangeResponseStatus<T>> {
+        let res = serde_json::to_string(payload).map_err(|e| Error::JsonParse(e.to_string()))?;
+        if self.dry_run {
+            info!("Dry run, not sending request {res:?}");
+            return serde_json::from_value(dry_run_response(&payload.action))
+                .map_err(|e| Error::JsonParse(e.to_string()));
+        }
         debug!("Sending request {res:?}");
 
-        serde_json::from_str(
-            &self
-                .http_client
-                .post("/exchange", res)
-                .await
-                .map_err(|e| Error::JsonParse(e.to_string()))?,
-        )
-        .map_err(|e| Error::JsonParse(e.to_string()))
+        // Actions this version doesn't know are sent once with the lowest weight
+        let (weight, idempotent) = serde_json::from_value::<Actions>(payload.action.clone())
+            .map_or((1, false), |action| {
+                (action.weight(), action.is_idempotent())
+            });
+        let output = self
+            .http_client
+            .post("/exchange", res, weight, idempotent)
+            .await?;
+        serde_json::from_str(&output).map_err(|e| Error::JsonParse(e.to_string()))
     }
 
     pub async fn usdc_transfer(
         &self,
         amount: &str,
         destination: &str,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
         let wallet = wallet.unwrap_or(&self.wallet);
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
-
-        let timestamp = next_nonce();
+        let timestamp = self.nonce_manager.next_nonce(wallet.address())?;
         let usd_send = UsdSend {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
+            signature_chain_id: self.signature_chain_id,
+            hyperliquid_chain: self.hyperliquid_chain.clone(),
             destination: destination.to_string(),
             amount: amount.to_string(),
             time: timestamp,
         };
-        let signature = sign_typed_data(&usd_send, wallet)?;
+        let signature = sign_typed_data(&usd_send, wallet).await?;
         let action = serde_json::to_value(Actions::UsdSend(usd_send))
             .map_err(|e| Error::JsonParse(e.to_string()))?;
 
-        self.post(action, signature, timestamp).await
+        self.post(
+            action,
+            signature,
+            timestamp,
+            self.vault_address,
+            wallet.address(),
+        )
+        .await
     }
 
     pub async fn class_transfer(
         &self,
         usdc: f64,
         to_perp: bool,
-        wallet: Option<&LocalWallet>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
         // payload expects usdc without decimals
         let usdc = (usdc * 1e6).round() as u64;
         let wallet = wallet.unwrap_or(&self.wallet);
 
-        let timestamp = next_nonce();
-
         let action = Actions::SpotUser(SpotUser {
             class_transfer: ClassTransfer { usdc, to_perp },
         });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
-
-        self.post(action, signature, timestamp).await
+        self.post_l1_action(action, wallet, self.vault_for(options), options)
+            .await
     }
 
     pub async fn vault_transfer(
@@ -199,85 +417,361 @@ impl ExchangeClient {
         is_deposit: bool,
         usd: String,
         vault_address: Option<H160>,
-        wallet: Option<&LocalWallet>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
-        let vault_address = self
-            .vault_address
-            .or(vault_address)
+        let vault_address = vault_address
+            .or(self.vault_address)
             .ok_or_else(|| Error::VaultAddressNotFound)?;
         let wallet = wallet.unwrap_or(&self.wallet);
 
-        let timestamp = next_nonce();
-
         let action = Actions::VaultTransfer(VaultTransfer {
             vault_address,
             is_deposit,
             usd,
         });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
+        // Moves funds of the signing wallet, so it isn't sent on behalf of any vault
+        self.post_l1_action(action, wallet, None, options).await
+    }
+
+    /// Creates a sub-account of the signing wallet, returning its address on success
+    pub async fn create_sub_account(
+        &self,
+        name: &str,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus<CreateSubAccountResponse>> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+
+        let action = Actions::CreateSubAccount(CreateSubAccount {
+            name: name.to_string(),
+        });
+        self.post_l1_action(action, wallet, None, options).await
+    }
+
+    /// Moves USDC between the signing wallet and one of its sub-accounts
+    pub async fn sub_account_transfer(
+        &self,
+        sub_account_user: H160,
+        is_deposit: bool,
+        usd: f64,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        // payload expects usd without decimals
+        let usd = (usd * 1e6).round() as u64;
+        let wallet = wallet.unwrap_or(&self.wallet);
+
+        let action = Actions::SubAccountTransfer(SubAccountUsdTransfer {
+            sub_account_user,
+            is_deposit,
+            usd,
+        });
+        self.post_l1_action(action, wallet, None, options).await
+    }
+
+    /// Moves a spot token between the signing wallet and one of its sub-accounts. `token` is
+    /// in the `NAME:0x...` token id format used by `spot_transfer`.
+    pub async fn sub_account_spot_transfer(
+        &self,
+        sub_account_user: H160,
+        is_deposit: bool,
+        token: &str,
+        amount: &str,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+
+        let action = Actions::SubAccountSpotTransfer(SubAccountSpotTransfer {
+            sub_account_user,
+            is_deposit,
+            token: token.to_string(),
+            amount: amount.to_string(),
+        });
+        self.post_l1_action(action, wallet, None, options).await
+    }
 
-        self.post(action, signature, timestamp).await
+    /// Trades on behalf of a sub-account. Actions are still signed by the master wallet, with
+    /// the sub-account address in place of the vault address.
+    pub fn sub_account(&self, sub_account_user: H160) -> SubAccountClient<'_, S> {
+        SubAccountClient {
+            exchange_client: self,
+            sub_account_user,
+        }
     }
 
     pub async fn order(
         &self,
         order: ClientOrderRequest,
-        wallet: Option<&LocalWallet>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
-        self.bulk_order(vec![order], wallet).await
+        self.bulk_order(vec![order], options, wallet).await
     }
 
     pub async fn bulk_order(
         &self,
         orders: Vec<ClientOrderRequest>,
-        wallet: Option<&LocalWallet>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.bulk_order_with_grouping(orders, Grouping::Na, options, wallet)
+            .await
+    }
+
+    pub async fn bulk_order_with_grouping(
+        &self,
+        orders: Vec<ClientOrderRequest>,
+        grouping: Grouping,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.bulk_order_with_grouping_for_vault(
+            orders,
+            grouping,
+            None,
+            self.vault_for(options),
+            options,
+            wallet,
+        )
+        .await
+    }
+
+    /// Places orders attributed to `builder`, which must have been approved by the user with
+    /// `approve_builder_fee` for at least `builder.fee`
+    pub async fn bulk_order_with_builder(
+        &self,
+        orders: Vec<ClientOrderRequest>,
+        builder: BuilderInfo,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.bulk_order_with_grouping_for_vault(
+            orders,
+            Grouping::Na,
+            Some(builder),
+            self.vault_for(options),
+            options,
+            wallet,
+        )
+        .await
+    }
+
+    pub(crate) async fn bulk_order_with_grouping_for_vault(
+        &self,
+        orders: Vec<ClientOrderRequest>,
+        grouping: Grouping,
+        builder: Option<BuilderInfo>,
+        vault_address: Option<H160>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
         let wallet = wallet.unwrap_or(&self.wallet);
-        let timestamp = next_nonce();
+        let assets = self
+            .info_client
+            .assets_for(orders.iter().map(|order| order.asset.as_str()))
+            .await?;
 
         let mut transformed_orders = Vec::new();
 
         for order in orders {
-            transformed_orders.push(order.convert(&self.coin_to_asset)?);
+            transformed_orders.push(
+                self.normalize(order, &assets)?
+                    .convert(&assets.coin_to_asset)?,
+            );
         }
 
         let action = Actions::Order(BulkOrder {
             orders: transformed_orders,
-            grouping: "na".to_string(),
+            grouping,
+            builder,
         });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+        self.post_l1_action(action, wallet, vault_address, options)
+            .await
+    }
 
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
-        self.post(action, signature, timestamp).await
+    pub async fn market_open(
+        &self,
+        coin: &str,
+        is_buy: bool,
+        sz: f64,
+        slippage: Option<f64>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let limit_px = self.slippage_price(coin, is_buy, slippage).await?;
+
+        let order = ClientOrderRequest {
+            asset: coin.to_string(),
+            is_buy,
+            reduce_only: false,
+            limit_px,
+            sz,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Ioc }),
+        };
+
+        self.order(order, options, wallet).await
+    }
+
+    /// Closes `sz` of the position of `user`, or all of it. `user` defaults to the vault the
+    /// action is sent for, else the signing wallet, so pass it when signing with an agent.
+    pub async fn market_close(
+        &self,
+        coin: &str,
+        sz: Option<f64>,
+        slippage: Option<f64>,
+        user: Option<H160>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let user = user
+            .or_else(|| self.vault_for(options))
+            .unwrap_or_else(|| wallet.unwrap_or(&self.wallet).address());
+
+        let position = self
+            .info_client
+            .user_state(user)
+            .await?
+            .asset_positions
+            .into_iter()
+            .find(|asset_position| asset_position.position.coin == coin)
+            .ok_or(Error::PositionNotFound)?;
+        let szi: f64 = position
+            .position
+            .szi
+            .parse()
+            .map_err(|_| Error::FloatStringParse)?;
+
+        let is_buy = szi < 0.0;
+        let limit_px = self.slippage_price(coin, is_buy, slippage).await?;
+
+        let order = ClientOrderRequest {
+            asset: coin.to_string(),
+            is_buy,
+            reduce_only: true,
+            limit_px,
+            sz: sz.unwrap_or(szi.abs()),
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Ioc }),
+        };
+
+        self.order(order, options, wallet).await
+    }
+
+    /// Prices averaging to the mid of `coin`: its mid in allMids or, since not every coin is
+    /// quoted there, the best bid and ask of its book
+    pub(super) async fn mid_quotes(&self, coin: &str) -> Result<Vec<String>> {
+        if let Some(mid) = self.info_client.all_mids().await?.remove(coin) {
+            return Ok(vec![mid]);
+        }
+
+        let levels = self.info_client.l2_snapshot(coin.to_string()).await?.levels;
+        (0..2)
+            .map(|side| {
+                levels
+                    .get(side)
+                    .and_then(|side| side.first())
+                    .map(|level| level.px.clone())
+                    .ok_or_else(|| Error::UnexpectedResponse(format!("empty book for {coin}")))
+            })
+            .collect()
+    }
+
+    async fn slippage_price(&self, coin: &str, is_buy: bool, slippage: Option<f64>) -> Result<f64> {
+        let slippage = slippage.unwrap_or(DEFAULT_SLIPPAGE);
+
+        let quotes = self.mid_quotes(coin).await?;
+        let mid = quotes
+            .iter()
+            .map(|px| parse_number(px))
+            .sum::<Result<f64>>()?
+            / quotes.len() as f64;
+
+        let px = if is_buy {
+            mid * (1.0 + slippage)
+        } else {
+            mid * (1.0 - slippage)
+        };
+
+        self.info_client
+            .assets_for([coin])
+            .await?
+            .normalizer
+            .round_px(coin, px)
+    }
+
+    pub async fn bracket_order(
+        &self,
+        entry: ClientOrderRequest,
+        take_profit_px: Option<f64>,
+        stop_loss_px: Option<f64>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<BracketOrderStatuses> {
+        let orders = bracket_orders(entry, take_profit_px, stop_loss_px);
+
+        let response = self
+            .bulk_order_with_grouping(orders, Grouping::NormalTpsl, options, wallet)
+            .await?;
+        let response = match response {
+            ExchangeResponseStatus::Ok(response) => response,
+            ExchangeResponseStatus::Err(e) => return Err(Error::Exchange(e)),
+        };
+
+        let expected_statuses =
+            1 + usize::from(take_profit_px.is_some()) + usize::from(stop_loss_px.is_some());
+        let mut statuses = match response.data {
+            Some(data) if data.statuses.len() == expected_statuses => data.statuses.into_iter(),
+            data => return Err(Error::UnexpectedResponse(format!("{data:?}"))),
+        };
+        let entry = statuses
+            .next()
+            .ok_or_else(|| Error::UnexpectedResponse("missing entry status".to_string()))?;
+
+        Ok(BracketOrderStatuses {
+            entry,
+            take_profit: take_profit_px.and_then(|_| statuses.next()),
+            stop_loss: stop_loss_px.and_then(|_| statuses.next()),
+        })
     }
 
     pub async fn cancel(
         &self,
         cancel: ClientCancelRequest,
-        wallet: Option<&LocalWallet>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
-        self.bulk_cancel(vec![cancel], wallet).await
+        self.bulk_cancel(vec![cancel], options, wallet).await
     }
 
     pub async fn bulk_cancel(
         &self,
         cancels: Vec<ClientCancelRequest>,
-        wallet: Option<&LocalWallet>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.bulk_cancel_for_vault(cancels, self.vault_for(options), options, wallet)
+            .await
+    }
+
+    pub(crate) async fn bulk_cancel_for_vault(
+        &self,
+        cancels: Vec<ClientCancelRequest>,
+        vault_address: Option<H160>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
         let wallet = wallet.unwrap_or(&self.wallet);
-        let timestamp = next_nonce();
+        let assets = self
+            .info_client
+            .assets_for(cancels.iter().map(|cancel| cancel.asset.as_str()))
+            .await?;
 
         let mut transformed_cancels = Vec::new();
         for cancel in cancels.into_iter() {
-            let &asset = self
-                .coin_to_asset
-                .get(&cancel.asset)
-                .ok_or(Error::AssetNotFound)?;
+            let asset = assets.asset(&cancel.asset)?;
             transformed_cancels.push(CancelRequest {
                 asset,
                 oid: cancel.oid,
@@ -287,37 +781,46 @@ impl ExchangeClient {
         let action = Actions::Cancel(BulkCancel {
             cancels: transformed_cancels,
         });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
-
-        self.post(action, signature, timestamp).await
+        self.post_l1_action(action, wallet, vault_address, options)
+            .await
     }
 
     pub async fn cancel_by_cloid(
         &self,
         cancel: ClientCancelRequestCloid,
-        wallet: Option<&LocalWallet>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
-        self.bulk_cancel_by_cloid(vec![cancel], wallet).await
+        self.bulk_cancel_by_cloid(vec![cancel], options, wallet)
+            .await
     }
 
     pub async fn bulk_cancel_by_cloid(
         &self,
         cancels: Vec<ClientCancelRequestCloid>,
-        wallet: Option<&LocalWallet>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.bulk_cancel_by_cloid_for_vault(cancels, self.vault_for(options), options, wallet)
+            .await
+    }
+
+    pub(crate) async fn bulk_cancel_by_cloid_for_vault(
+        &self,
+        cancels: Vec<ClientCancelRequestCloid>,
+        vault_address: Option<H160>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
         let wallet = wallet.unwrap_or(&self.wallet);
-        let timestamp = next_nonce();
+        let assets = self
+            .info_client
+            .assets_for(cancels.iter().map(|cancel| cancel.asset.as_str()))
+            .await?;
 
         let mut transformed_cancels: Vec<CancelRequestCloid> = Vec::new();
         for cancel in cancels.into_iter() {
-            let &asset = self
-                .coin_to_asset
-                .get(&cancel.asset)
-                .ok_or(Error::AssetNotFound)?;
+            let asset = assets.asset(&cancel.asset)?;
             transformed_cancels.push(CancelRequestCloid {
                 asset,
                 cloid: uuid_to_hex_string(cancel.cloid),
@@ -328,122 +831,420 @@ impl ExchangeClient {
             cancels: transformed_cancels,
         });
 
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
+        self.post_l1_action(action, wallet, vault_address, options)
+            .await
+    }
 
-        self.post(action, signature, timestamp).await
+    pub async fn modify_order(
+        &self,
+        oid: u64,
+        order: ClientOrderRequest,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.bulk_modify(vec![ClientModifyRequest { oid, order }], options, wallet)
+            .await
     }
 
-    pub async fn update_leverage(
+    pub async fn bulk_modify(
         &self,
-        leverage: u32,
-        coin: &str,
-        is_cross: bool,
-        wallet: Option<&LocalWallet>,
+        modifies: Vec<ClientModifyRequest>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
+        self.bulk_modify_for_vault(modifies, self.vault_for(options), options, wallet)
+            .await
+    }
 
-        let timestamp = next_nonce();
+    pub(crate) async fn bulk_modify_for_vault(
+        &self,
+        modifies: Vec<ClientModifyRequest>,
+        vault_address: Option<H160>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let assets = self
+            .info_client
+            .assets_for(modifies.iter().map(|modify| modify.order.asset.as_str()))
+            .await?;
+
+        let mut transformed_modifies = Vec::new();
+        for modify in modifies.into_iter() {
+            transformed_modifies.push(ModifyRequest {
+                oid: OidOrCloid::Oid(modify.oid),
+                order: self
+                    .normalize(modify.order, &assets)?
+                    .convert(&assets.coin_to_asset)?,
+            });
+        }
 
-        let &asset_index = self.coin_to_asset.get(coin).ok_or(Error::AssetNotFound)?;
-        let action = Actions::UpdateLeverage(UpdateLeverage {
-            asset: asset_index,
-            is_cross,
-            leverage,
+        let action = Actions::BatchModify(BulkModify {
+            modifies: transformed_modifies,
         });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
+        self.post_l1_action(action, wallet, vault_address, options)
+            .await
+    }
+
+    pub async fn modify_order_by_cloid(
+        &self,
+        cloid: Uuid,
+        order: ClientOrderRequest,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.bulk_modify_by_cloid(
+            vec![ClientModifyRequestCloid { cloid, order }],
+            options,
+            wallet,
+        )
+        .await
+    }
 
-        self.post(action, signature, timestamp).await
+    pub async fn bulk_modify_by_cloid(
+        &self,
+        modifies: Vec<ClientModifyRequestCloid>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.bulk_modify_by_cloid_for_vault(modifies, self.vault_for(options), options, wallet)
+            .await
     }
 
-    pub async fn update_isolated_margin(
+    pub(crate) async fn bulk_modify_by_cloid_for_vault(
         &self,
-        amount: f64,
-        coin: &str,
-        wallet: Option<&LocalWallet>,
+        modifies: Vec<ClientModifyRequestCloid>,
+        vault_address: Option<H160>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
         let wallet = wallet.unwrap_or(&self.wallet);
+        let assets = self
+            .info_client
+            .assets_for(modifies.iter().map(|modify| modify.order.asset.as_str()))
+            .await?;
+
+        let mut transformed_modifies = Vec::new();
+        for modify in modifies.into_iter() {
+            transformed_modifies.push(ModifyRequest {
+                oid: OidOrCloid::Cloid(uuid_to_hex_string(modify.cloid)),
+                order: self
+                    .normalize(modify.order, &assets)?
+                    .convert(&assets.coin_to_asset)?,
+            });
+        }
 
-        let amount = (amount * 1_000_000.0).round() as i64;
-        let timestamp = next_nonce();
-
-        let &asset_index = self.coin_to_asset.get(coin).ok_or(Error::AssetNotFound)?;
-        let action = Actions::UpdateIsolatedMargin(UpdateIsolatedMargin {
-            asset: asset_index,
-            is_buy: true,
-            ntli: amount,
+        let action = Actions::BatchModify(BulkModify {
+            modifies: transformed_modifies,
         });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
-
-        self.post(action, signature, timestamp).await
+        self.post_l1_action(action, wallet, vault_address, options)
+            .await
     }
 
-    pub async fn approve_agent(
+    /// Places a TWAP order, executed by the exchange in slices over `minutes`
+    pub async fn twap_order(
         &self,
-        wallet: Option<&LocalWallet>,
-    ) -> Result<(String, ExchangeResponseStatus)> {
+        twap: ClientTwapRequest,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus<TwapResponse>> {
         let wallet = wallet.unwrap_or(&self.wallet);
-        let key = H256::from(generate_random_key()?).encode_hex()[2..].to_string();
+        let assets = self.info_client.assets_for([twap.asset.as_str()]).await?;
 
-        let address = key
-            .parse::<LocalWallet>()
-            .map_err(|e| Error::PrivateKeyParse(e.to_string()))?
-            .address();
-
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
-
-        let nonce = next_nonce();
-        let approve_agent = ApproveAgent {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
-            agent_address: address,
-            agent_name: None,
-            nonce,
+        let twap = match self.normalization {
+            Some(mode) => ClientTwapRequest {
+                sz: assets.normalizer.normalize_sz(&twap.asset, twap.sz, mode)?,
+                ..twap
+            },
+            None => twap,
         };
-        let signature = sign_typed_data(&approve_agent, wallet)?;
-        let action = serde_json::to_value(Actions::ApproveAgent(approve_agent))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
-        Ok((key, self.post(action, signature, nonce).await?))
+        let action = Actions::TwapOrder(TwapOrder {
+            twap: twap.convert(&assets.coin_to_asset)?,
+        });
+        self.post_l1_action(action, wallet, self.vault_for(options), options)
+            .await
     }
 
-    pub async fn withdraw_from_bridge(
+    pub async fn twap_cancel(
         &self,
-        amount: &str,
-        destination: &str,
-        wallet: Option<&LocalWallet>,
-    ) -> Result<ExchangeResponseStatus> {
+        coin: &str,
+        twap_id: u64,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus<TwapResponse>> {
         let wallet = wallet.unwrap_or(&self.wallet);
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
 
-        let timestamp = next_nonce();
-        let withdraw = Withdraw3 {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
-            destination: destination.to_string(),
-            amount: amount.to_string(),
-            time: timestamp,
+        let asset = self.info_client.assets_for([coin]).await?.asset(coin)?;
+        let action = Actions::TwapCancel(TwapCancel { asset, twap_id });
+        self.post_l1_action(action, wallet, self.vault_for(options), options)
+            .await
+    }
+
+    pub async fn update_leverage(
+        &self,
+        leverage: u32,
+        coin: &str,
+        is_cross: bool,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.update_leverage_for_vault(
+            leverage,
+            coin,
+            is_cross,
+            self.vault_for(options),
+            options,
+            wallet,
+        )
+        .await
+    }
+
+    pub(crate) async fn update_leverage_for_vault(
+        &self,
+        leverage: u32,
+        coin: &str,
+        is_cross: bool,
+        vault_address: Option<H160>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+
+        let asset_index = self.info_client.assets_for([coin]).await?.asset(coin)?;
+        let action = Actions::UpdateLeverage(UpdateLeverage {
+            asset: asset_index,
+            is_cross,
+            leverage,
+        });
+        self.post_l1_action(action, wallet, vault_address, options)
+            .await
+    }
+
+    pub async fn update_isolated_margin(
+        &self,
+        amount: f64,
+        coin: &str,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.update_isolated_margin_for_vault(
+            amount,
+            coin,
+            self.vault_for(options),
+            options,
+            wallet,
+        )
+        .await
+    }
+
+    pub(crate) async fn update_isolated_margin_for_vault(
+        &self,
+        amount: f64,
+        coin: &str,
+        vault_address: Option<H160>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+
+        let amount = (amount * 1_000_000.0).round() as i64;
+
+        let asset_index = self.info_client.assets_for([coin]).await?.asset(coin)?;
+        let action = Actions::UpdateIsolatedMargin(UpdateIsolatedMargin {
+            asset: asset_index,
+            is_buy: true,
+            ntli: amount,
+        });
+        self.post_l1_action(action, wallet, vault_address, options)
+            .await
+    }
+
+    /// Schedules a cancel of all open orders at `time` (ms since epoch), which must be at least
+    /// 5 seconds in the future. Passing `None` removes the scheduled cancel.
+    pub async fn schedule_cancel(
+        &self,
+        time: Option<u64>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+
+        let action = Actions::ScheduleCancel(ScheduleCancel { time });
+        self.post_l1_action(action, wallet, self.vault_for(options), options)
+            .await
+    }
+
+    /// Dead man's switch: every `interval`, schedules a cancel of all open orders `timeout` from
+    /// now. The task stops once the client is dropped or the handle is aborted, leaving the last
+    /// deadline in place. `timeout` should be comfortably longer than `interval`.
+    pub fn spawn_cancel_heartbeat(
+        client: &Arc<ExchangeClient<S>>,
+        interval: Duration,
+        timeout: Duration,
+    ) -> JoinHandle<()>
+    where
+        S: 'static,
+    {
+        let client: Weak<ExchangeClient<S>> = Arc::downgrade(client);
+        spawn(async move {
+            let mut ticker = time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let Some(client) = client.upgrade() else {
+                    break;
+                };
+                let deadline = now_timestamp_ms() + timeout.as_millis() as u64;
+                match client.schedule_cancel(Some(deadline), None, None).await {
+                    Ok(ExchangeResponseStatus::Ok(_)) => {}
+                    Ok(ExchangeResponseStatus::Err(e)) => {
+                        error!("Error scheduling cancel: {e}")
+                    }
+                    Err(e) => error!("Error scheduling cancel: {e}"),
+                }
+            }
+        })
+    }
+
+    /// Generates a new unnamed agent and approves it. This replaces the previous unnamed agent.
+    pub async fn approve_agent(
+        &self,
+        wallet: Option<&S>,
+    ) -> Result<(AgentCredentials, ExchangeResponseStatus)> {
+        self.approve_generated_agent(None, wallet).await
+    }
+
+    /// Generates a new agent and approves it under `name`, replacing any agent already
+    /// registered under that name.
+    pub async fn approve_named_agent(
+        &self,
+        name: &str,
+        wallet: Option<&S>,
+    ) -> Result<(AgentCredentials, ExchangeResponseStatus)> {
+        self.approve_generated_agent(Some(name.to_string()), wallet)
+            .await
+    }
+
+    async fn approve_generated_agent(
+        &self,
+        name: Option<String>,
+        wallet: Option<&S>,
+    ) -> Result<(AgentCredentials, ExchangeResponseStatus)> {
+        let key = H256::from(generate_random_key()?).encode_hex()[2..].to_string();
+
+        let address = Signer::address(
+            &key.parse::<LocalWallet>()
+                .map_err(|e| Error::PrivateKeyParse(e.to_string()))?,
+        );
+
+        let response = self
+            .approve_agent_address(address, name.as_deref(), wallet)
+            .await?;
+        let credentials = AgentCredentials {
+            name,
+            address,
+            private_key: key,
         };
-        let signature = sign_typed_data(&withdraw, wallet)?;
+        Ok((credentials, response))
+    }
+
+    /// Allows `builder` to charge up to `max_fee_rate` (e.g. "0.01%") on orders it places for
+    /// the signing user
+    pub async fn approve_builder_fee(
+        &self,
+        builder: H160,
+        max_fee_rate: &str,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+
+        let nonce = self.nonce_manager.next_nonce(wallet.address())?;
+        let approve_builder_fee = ApproveBuilderFee {
+            signature_chain_id: self.signature_chain_id,
+            hyperliquid_chain: self.hyperliquid_chain.clone(),
+            max_fee_rate: max_fee_rate.to_string(),
+            builder,
+            nonce,
+        };
+        let signature = sign_typed_data(&approve_builder_fee, wallet).await?;
+        let action = serde_json::to_value(Actions::ApproveBuilderFee(approve_builder_fee))
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        self.post(
+            action,
+            signature,
+            nonce,
+            self.vault_address,
+            wallet.address(),
+        )
+        .await
+    }
+
+    /// Approves an agent whose key is managed by the caller
+    pub async fn approve_agent_address(
+        &self,
+        agent_address: H160,
+        name: Option<&str>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+
+        let nonce = self.nonce_manager.next_nonce(wallet.address())?;
+        let approve_agent = ApproveAgent {
+            signature_chain_id: self.signature_chain_id,
+            hyperliquid_chain: self.hyperliquid_chain.clone(),
+            agent_address,
+            agent_name: name.map(|name| name.to_string()),
+            nonce,
+        };
+        let signature = sign_typed_data(&approve_agent, wallet).await?;
+        let action = serde_json::to_value(Actions::ApproveAgent(approve_agent))
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        self.post(
+            action,
+            signature,
+            nonce,
+            self.vault_address,
+            wallet.address(),
+        )
+        .await
+    }
+
+    /// Revokes the agent registered under `name` (or the unnamed agent) by approving the zero
+    /// address in its place
+    pub async fn revoke_agent(
+        &self,
+        name: Option<&str>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.approve_agent_address(H160::zero(), name, wallet).await
+    }
+
+    pub async fn withdraw_from_bridge(
+        &self,
+        amount: &str,
+        destination: &str,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let timestamp = self.nonce_manager.next_nonce(wallet.address())?;
+        let withdraw = Withdraw3 {
+            signature_chain_id: self.signature_chain_id,
+            hyperliquid_chain: self.hyperliquid_chain.clone(),
+            destination: destination.to_string(),
+            amount: amount.to_string(),
+            time: timestamp,
+        };
+        let signature = sign_typed_data(&withdraw, wallet).await?;
         let action = serde_json::to_value(Actions::Withdraw3(withdraw))
             .map_err(|e| Error::JsonParse(e.to_string()))?;
 
-        self.post(action, signature, timestamp).await
+        self.post(
+            action,
+            signature,
+            timestamp,
+            self.vault_address,
+            wallet.address(),
+        )
+        .await
     }
 
     pub async fn spot_transfer(
@@ -451,41 +1252,79 @@ impl ExchangeClient {
         amount: &str,
         destination: &str,
         token: &str,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
         let wallet = wallet.unwrap_or(&self.wallet);
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
-
-        let timestamp = next_nonce();
+        let timestamp = self.nonce_manager.next_nonce(wallet.address())?;
         let spot_send = SpotSend {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
+            signature_chain_id: self.signature_chain_id,
+            hyperliquid_chain: self.hyperliquid_chain.clone(),
             destination: destination.to_string(),
             amount: amount.to_string(),
             time: timestamp,
             token: token.to_string(),
         };
-        let signature = sign_typed_data(&spot_send, wallet)?;
+        let signature = sign_typed_data(&spot_send, wallet).await?;
         let action = serde_json::to_value(Actions::SpotSend(spot_send))
             .map_err(|e| Error::JsonParse(e.to_string()))?;
 
-        self.post(action, signature, timestamp).await
+        self.post(
+            action,
+            signature,
+            timestamp,
+            self.vault_address,
+            wallet.address(),
+        )
+        .await
+    }
+}
+
+fn bracket_orders(
+    entry: ClientOrderRequest,
+    take_profit_px: Option<f64>,
+    stop_loss_px: Option<f64>,
+) -> Vec<ClientOrderRequest> {
+    let exit_leg = |trigger_px: f64, tpsl: TpSl| ClientOrderRequest {
+        asset: entry.asset.clone(),
+        is_buy: !entry.is_buy,
+        reduce_only: true,
+        limit_px: trigger_px,
+        sz: entry.sz,
+        cloid: None,
+        order_type: ClientOrder::Trigger(ClientTrigger {
+            is_market: true,
+            trigger_px,
+            tpsl,
+        }),
+    };
+
+    let mut orders = Vec::new();
+    if let Some(take_profit_px) = take_profit_px {
+        orders.push(exit_leg(take_profit_px, TpSl::Tp));
+    }
+    if let Some(stop_loss_px) = stop_loss_px {
+        orders.push(exit_leg(stop_loss_px, TpSl::Sl));
     }
+    orders.insert(0, entry);
+    orders
 }
 
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
+    use std::{collections::HashMap, str::FromStr};
 
     use super::*;
     use crate::{
-        exchange::order::{Limit, OrderRequest, Trigger},
-        Order,
+        exchange::{
+            modify::{ModifyRequest, OidOrCloid},
+            order::{Limit, OrderRequest, Trigger},
+            twap::TwapRequest,
+        },
+        transport::tests::mock_api,
+        ExchangeDataStatus, MockTransport, Order, ARBITRUM_ONE_CHAIN_ID,
     };
+    use async_trait::async_trait;
+    use serde_json::json;
 
     fn get_wallet() -> Result<LocalWallet> {
         let priv_key = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e";
@@ -494,8 +1333,16 @@ mod tests {
             .map_err(|e| Error::Wallet(e.to_string()))
     }
 
-    #[test]
-    fn test_limit_order_action_hashing() -> Result<()> {
+    async fn mock_exchange_client<S: HyperliquidSigner>(
+        transport: Arc<MockTransport>,
+        wallet: S,
+        base_url: BaseUrl,
+    ) -> Result<ExchangeClient<S>> {
+        ExchangeClient::with_transport(transport, wallet, Some(base_url), None, None, None).await
+    }
+
+    #[tokio::test]
+    async fn test_limit_order_action_hashing() -> Result<()> {
         let wallet = get_wallet()?;
         let action = Actions::Order(BulkOrder {
             orders: vec![OrderRequest {
@@ -504,26 +1351,85 @@ mod tests {
                 limit_px: "2000.0".to_string(),
                 sz: "3.5".to_string(),
                 reduce_only: false,
-                order_type: Order::Limit(Limit {
-                    tif: "Ioc".to_string(),
-                }),
+                order_type: Order::Limit(Limit { tif: Tif::Ioc }),
                 cloid: None,
             }],
-            grouping: "na".to_string(),
+            grouping: Grouping::Na,
+            builder: None,
         });
-        let connection_id = action.hash(1583838, None)?;
+        let connection_id = action.hash(1583838, None, None)?;
 
-        let signature = sign_l1_action(&wallet, connection_id, true)?;
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
         assert_eq!(signature.to_string(), "77957e58e70f43b6b68581f2dc42011fc384538a2e5b7bf42d5b936f19fbb67360721a8598727230f67080efee48c812a6a4442013fd3b0eed509171bef9f23f1c");
 
-        let signature = sign_l1_action(&wallet, connection_id, false)?;
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
         assert_eq!(signature.to_string(), "cd0925372ff1ed499e54883e9a6205ecfadec748f80ec463fe2f84f1209648776377961965cb7b12414186b1ea291e95fd512722427efcbcfb3b0b2bcd4d79d01c");
 
         Ok(())
     }
 
-    #[test]
-    fn test_limit_order_action_hashing_with_cloid() -> Result<()> {
+    #[tokio::test]
+    async fn test_limit_order_action_hashing_for_sub_account() -> Result<()> {
+        let wallet = get_wallet()?;
+        let action = Actions::Order(BulkOrder {
+            orders: vec![OrderRequest {
+                asset: 1,
+                is_buy: true,
+                limit_px: "2000.0".to_string(),
+                sz: "3.5".to_string(),
+                reduce_only: false,
+                order_type: Order::Limit(Limit { tif: Tif::Ioc }),
+                cloid: None,
+            }],
+            grouping: Grouping::Na,
+            builder: None,
+        });
+        let sub_account_user = H160::from_str("0x1d9470d4b963f552e6f671a81619d395877bf409")
+            .map_err(|e| Error::GenericParse(e.to_string()))?;
+        let connection_id = action.hash(1583838, Some(sub_account_user), None)?;
+
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(signature.to_string(), "9e69b27dd517a439765fc478d1652028602a72ffce5c403bd7dbf110b9ef2a6a402941b577f4a3a28d3b507eeaf9ab2b0a991c46ae578bb7e2ff81f623c106141b");
+
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
+        assert_eq!(signature.to_string(), "fc2b33be72ebdedb604a40fe7e972179f955ab451ad925bdf5a3dd1a2ffe215753a51553f2e1fe90bb3c4442f735ac4bb759331ab2cb4dcf2a8fce50e6a3e6f31c");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_limit_order_action_hashing_with_builder() -> Result<()> {
+        let wallet = get_wallet()?;
+        let action = Actions::Order(BulkOrder {
+            orders: vec![OrderRequest {
+                asset: 1,
+                is_buy: true,
+                limit_px: "2000.0".to_string(),
+                sz: "3.5".to_string(),
+                reduce_only: false,
+                order_type: Order::Limit(Limit { tif: Tif::Ioc }),
+                cloid: None,
+            }],
+            grouping: Grouping::Na,
+            builder: Some(BuilderInfo {
+                builder: H160::from_str("0x1d9470d4b963f552e6f671a81619d395877bf409")
+                    .map_err(|e| Error::GenericParse(e.to_string()))?,
+                fee: 10,
+            }),
+        });
+        let connection_id = action.hash(1583838, None, None)?;
+
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(signature.to_string(), "660b408d7d482703de91626cb30318b89407b510d8ecf01603f847b4e80d632c15c8ea83a10c0b094b9eab2f513526d852fdba3802cde91b000a5234992276a41c");
+
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
+        assert_eq!(signature.to_string(), "bf59b606dcda6c200366ac9445c6b736001aa8c58dd8d28b1aeb66484bcefc1c059aad6baa8aefaf7fd76de1a9a255a59c3a52f294ba41848c64b0f4585ffdc91b");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_limit_order_action_hashing_with_cloid() -> Result<()> {
         let cloid = uuid::Uuid::from_str("1e60610f-0b3d-4205-97c8-8c1fed2ad5ee")
             .map_err(|_e| uuid::Uuid::new_v4());
         let wallet = get_wallet()?;
@@ -534,34 +1440,33 @@ mod tests {
                 limit_px: "2000.0".to_string(),
                 sz: "3.5".to_string(),
                 reduce_only: false,
-                order_type: Order::Limit(Limit {
-                    tif: "Ioc".to_string(),
-                }),
+                order_type: Order::Limit(Limit { tif: Tif::Ioc }),
                 cloid: Some(uuid_to_hex_string(cloid.unwrap())),
             }],
-            grouping: "na".to_string(),
+            grouping: Grouping::Na,
+            builder: None,
         });
-        let connection_id = action.hash(1583838, None)?;
+        let connection_id = action.hash(1583838, None, None)?;
 
-        let signature = sign_l1_action(&wallet, connection_id, true)?;
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
         assert_eq!(signature.to_string(), "d3e894092eb27098077145714630a77bbe3836120ee29df7d935d8510b03a08f456de5ec1be82aa65fc6ecda9ef928b0445e212517a98858cfaa251c4cd7552b1c");
 
-        let signature = sign_l1_action(&wallet, connection_id, false)?;
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
         assert_eq!(signature.to_string(), "3768349dbb22a7fd770fc9fc50c7b5124a7da342ea579b309f58002ceae49b4357badc7909770919c45d850aabb08474ff2b7b3204ae5b66d9f7375582981f111c");
 
         Ok(())
     }
 
-    #[test]
-    fn test_tpsl_order_action_hashing() -> Result<()> {
+    #[tokio::test]
+    async fn test_tpsl_order_action_hashing() -> Result<()> {
         for (tpsl, mainnet_signature, testnet_signature) in [
             (
-                "tp",
+                TpSl::Tp,
                 "b91e5011dff15e4b4a40753730bda44972132e7b75641f3cac58b66159534a170d422ee1ac3c7a7a2e11e298108a2d6b8da8612caceaeeb3e571de3b2dfda9e41b",
                 "6df38b609904d0d4439884756b8f366f22b3a081801dbdd23f279094a2299fac6424cb0cdc48c3706aeaa368f81959e91059205403d3afd23a55983f710aee871b"
             ),
             (
-                "sl",
+                TpSl::Sl,
                 "8456d2ace666fce1bee1084b00e9620fb20e810368841e9d4dd80eb29014611a0843416e51b1529c22dd2fc28f7ff8f6443875635c72011f60b62cbb8ce90e2d1c",
                 "eb5bdb52297c1d19da45458758bd569dcb24c07e5c7bd52cf76600fd92fdd8213e661e21899c985421ec018a9ee7f3790e7b7d723a9932b7b5adcd7def5354601c"
             )
@@ -578,26 +1483,266 @@ mod tests {
                         order_type: Order::Trigger(Trigger {
                             trigger_px: "2000.0".to_string(),
                             is_market: true,
-                            tpsl: tpsl.to_string(),
+                            tpsl,
                         }),
                         cloid: None,
                     }
                 ],
-                grouping: "na".to_string(),
+                grouping: Grouping::Na,
+                builder: None,
             });
-            let connection_id = action.hash(1583838, None)?;
+            let connection_id = action.hash(1583838, None, None)?;
 
-            let signature = sign_l1_action(&wallet, connection_id, true)?;
+            let signature = sign_l1_action(&wallet, connection_id, true).await?;
             assert_eq!(signature.to_string(), mainnet_signature);
 
-            let signature = sign_l1_action(&wallet, connection_id, false)?;
+            let signature = sign_l1_action(&wallet, connection_id, false).await?;
             assert_eq!(signature.to_string(), testnet_signature);
         }
         Ok(())
     }
 
-    #[test]
-    fn test_cancel_action_hashing() -> Result<()> {
+    fn get_modify_order() -> OrderRequest {
+        OrderRequest {
+            asset: 1,
+            is_buy: true,
+            limit_px: "2050.0".to_string(),
+            sz: "3.5".to_string(),
+            reduce_only: false,
+            order_type: Order::Limit(Limit { tif: Tif::Gtc }),
+            cloid: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_modify_action_hashing() -> Result<()> {
+        let wallet = get_wallet()?;
+        let action = Actions::Modify(ModifyRequest {
+            oid: OidOrCloid::Oid(82382),
+            order: get_modify_order(),
+        });
+        let connection_id = action.hash(1583838, None, None)?;
+
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(signature.to_string(), "fc8295b3a6c84a6b934ebaa2c1e0e342769864fca33ea3f9c589a34f7615cae35f25e41acddebac243e92975416816b64b31d0f39a32106c3ed379393ef76d301c");
+
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
+        assert_eq!(signature.to_string(), "04b73847e843947cc8890deae128d441ea9958690cf0f9fc61f3e3056d5ff4e76bf50e376ad65343ceda299d9630efff5e3a49f866fc601d94e29a618dc713d81c");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_batch_modify_action_hashing() -> Result<()> {
+        let wallet = get_wallet()?;
+        let action = Actions::BatchModify(BulkModify {
+            modifies: vec![ModifyRequest {
+                oid: OidOrCloid::Oid(82382),
+                order: get_modify_order(),
+            }],
+        });
+        let connection_id = action.hash(1583838, None, None)?;
+
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(signature.to_string(), "9ef10f5653c233b85a857c3924f6c88cd08e45f74aabf261b54e7d1a7a4014b60194cce51634040277a943c4127a91c091df2f7f97d605440a02f5943a3de59e1b");
+
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
+        assert_eq!(signature.to_string(), "b7fe0b9c32385977a923da6992921a3960a98ac566db26b6f1efc29bdded7eb72095ebd2bd5b6afad2746e0f87cbebf48caddbde66724ee565431dec083f78451b");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_batch_modify_action_hashing_with_cloid() -> Result<()> {
+        let cloid = uuid::Uuid::from_str("1e60610f-0b3d-4205-97c8-8c1fed2ad5ee")
+            .map_err(|e| Error::GenericParse(e.to_string()))?;
+        let wallet = get_wallet()?;
+        let action = Actions::BatchModify(BulkModify {
+            modifies: vec![ModifyRequest {
+                oid: OidOrCloid::Cloid(uuid_to_hex_string(cloid)),
+                order: get_modify_order(),
+            }],
+        });
+        let connection_id = action.hash(1583838, None, None)?;
+
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(signature.to_string(), "7b364760ec1f140a7c19fa9805faf5297771fe3cf16fd30d8e500f3d4bb1a73801b140b1a0cf16d101505d42579da48e7b55715b4e5816c7f50b9a0f4db37e601c");
+
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
+        assert_eq!(signature.to_string(), "2616adc2cb38f5821ef7ef63c851d59cc1b22a7516e484abb54eb7d5d43cb0e376a8d8876400c4fc6d5fdaa9ea1ba78f1e911a4a56181aad757cbf2aea598b271b");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_bracket_order_action_hashing() -> Result<()> {
+        let wallet = get_wallet()?;
+        let coin_to_asset = HashMap::from([("ETH".to_string(), 1)]);
+        let entry = ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px: 2000.0,
+            sz: 3.5,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+        };
+
+        let orders = bracket_orders(entry, Some(2100.0), Some(1900.0))
+            .into_iter()
+            .map(|order| order.convert(&coin_to_asset))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(orders.len(), 3);
+        assert!(orders[1..]
+            .iter()
+            .all(|order| !order.is_buy && order.reduce_only && order.sz == "3.5"));
+
+        let action = Actions::Order(BulkOrder {
+            orders,
+            grouping: Grouping::NormalTpsl,
+            builder: None,
+        });
+        let connection_id = action.hash(1583838, None, None)?;
+
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(signature.to_string(), "12cabfacfb32aa82e5c387a27054996311d4ba72a92aae0f842425e10e43626a1578d6d3b8912c2f563bee41611d7fd28981d76f7189eae3008e368f1fb745371b");
+
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
+        assert_eq!(signature.to_string(), "e397114a71983f33e0e8a895833b92b54dc52411d4f2e2b5a057368a76e8f2875235703ec5e951530014808cd92bc5eae7f70f01f95a1d9999ce4df9ad8ccc401b");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_schedule_cancel_action_hashing() -> Result<()> {
+        let wallet = get_wallet()?;
+        let action = Actions::ScheduleCancel(ScheduleCancel {
+            time: Some(1583848),
+        });
+        let connection_id = action.hash(1583838, None, None)?;
+
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(signature.to_string(), "b41fe7bf990a0b17485cb1643d1444e1cf8286cd9e53cb31c4e04fe0dd595480388908c0112de78e5fe22ca7abcca89b9629390fa1ba7d29f158fca60ca9c3361b");
+
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
+        assert_eq!(signature.to_string(), "e07028dd763c1949c6b52bc45c86edf1c331c52f33ed573923dc5e5593ce62ce0a472ba08deeef53300fa1b3917bb37cc9306fbefeafd246974261d42e88443e1c");
+
+        let action = Actions::ScheduleCancel(ScheduleCancel { time: None });
+        let connection_id = action.hash(1583838, None, None)?;
+
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(signature.to_string(), "d32e5413a14b9598a41e294fb2c401a2d7d17e76c21445f5cc335b1c9927a6d41af9b36c758ca1f0bf813678b01695625785bca6ddf09f17163879abea8f71751c");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_expires_after_action_hashing() -> Result<()> {
+        let wallet = get_wallet()?;
+        let action = Actions::ScheduleCancel(ScheduleCancel {
+            time: Some(1583848),
+        });
+        let connection_id = action.hash(1583838, None, Some(1593838))?;
+        assert_ne!(connection_id, action.hash(1583838, None, None)?);
+
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(signature.to_string(), "e0fd28b95876cb31a395cae6d580f96b70043c3b0563f80d35ef31ae11adda07797bf636a89eec5185546a20ef0d3e602a0d48e8fd9c4b42a9aea2454940f2081c");
+
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
+        assert_eq!(signature.to_string(), "a6c736a30b2d92a34d7ff8e6554389ea488d974e993f511cb5b4a19366008e771903e30f0601740a70c393bc2f17ded78f1d0fa640fd0895df76075ec99f8f941b");
+
+        let vault_address = H160::from_str("0x1719884eb866cb12b2287399b15f7db5e7d775ea")
+            .map_err(|e| Error::GenericParse(e.to_string()))?;
+        let connection_id = action.hash(1583838, Some(vault_address), Some(1593838))?;
+
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(signature.to_string(), "3a4b92e238ee629f2c062f66f0fe27999adb886f056b30a3886b18845b62ea48635100b9f9790af374f7e54af73a1e8e82c75b9e350eaf8e6db4f0deda5c28f61b");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_sub_account_action_hashing() -> Result<()> {
+        let wallet = get_wallet()?;
+        let action = Actions::CreateSubAccount(CreateSubAccount {
+            name: "example".to_string(),
+        });
+        let connection_id = action.hash(1583838, None, None)?;
+
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(signature.to_string(), "641ae838866f45167ac83ff50922925254bcd6e7df305cd9237a85b546a37d6a53bd2e567c6fdce4417272162303511731b77f0bf1db11d5b594133d969153d91b");
+
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
+        assert_eq!(signature.to_string(), "37c421eabcad25b7e8188ce2228aed96c0eaef2b59be180d0e0b30ccf68cde2d597d35c84da5ed512766c8131c1a63e45de8389e0790c9d7fa445ba610d7d1c51c");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sub_account_transfer_action_hashing() -> Result<()> {
+        let wallet = get_wallet()?;
+        let sub_account_user = H160::from_str("0x1d9470d4b963f552e6f671a81619d395877bf409")
+            .map_err(|e| Error::GenericParse(e.to_string()))?;
+        let action = Actions::SubAccountTransfer(SubAccountUsdTransfer {
+            sub_account_user,
+            is_deposit: true,
+            usd: 10_000_000,
+        });
+        let connection_id = action.hash(1583838, None, None)?;
+
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(signature.to_string(), "1163c196c1c3414274c5e8186e4fb215e1248c886d6dbdf3410ef1d33acc59737ec212db28c110992756b96fe290e4dc72eb84865b17ca7724b2569b5f678d001c");
+
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
+        assert_eq!(signature.to_string(), "65524b9410a7025c50b57e1d98d15ea132af133aeae5d73fc673f8c8766125727a1473eb361202e1b2c2c0e07ac02685ddf0a637dcb5587869deee73c8f0d9091c");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_twap_order_action_hashing() -> Result<()> {
+        let wallet = get_wallet()?;
+        let action = Actions::TwapOrder(TwapOrder {
+            twap: TwapRequest {
+                asset: 1,
+                is_buy: true,
+                sz: "3.5".to_string(),
+                reduce_only: false,
+                minutes: 30,
+                randomize: true,
+            },
+        });
+        let connection_id = action.hash(1583838, None, None)?;
+
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(signature.to_string(), "110eee2bedf0cc59d99ceea1aace3b605b9b14a499daa7f3ca73e33e6da185f873670abeb047ffe9990d5334b855cba68c47855eb6cdde52579caf934d01322b1b");
+
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
+        assert_eq!(signature.to_string(), "3d503305ba84f7d47805c8508bac6dbb53e053ddaec6206b14cf1aad7eba1ed76dd11ec1cbdd21ead38405cbe5e755347e894a70dba83b0fcc9c8c1987f715321c");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_twap_cancel_action_hashing() -> Result<()> {
+        let wallet = get_wallet()?;
+        let action = Actions::TwapCancel(TwapCancel {
+            asset: 1,
+            twap_id: 8123,
+        });
+        let connection_id = action.hash(1583838, None, None)?;
+
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(signature.to_string(), "3c3ad429fb3034db5923a565696bf5a03fc8830bb0f4d1385f2370b76cb8aaf361375b160633272e75443b5f07ac94a9a617d9e366c246b2de681d2eab39b6f41b");
+
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
+        assert_eq!(signature.to_string(), "9d0adeeeacc31a18fcd34f35547a46013ee1e490685a4f975a84ca221019fae73baa50a24fb50d80165a5818b3ca458dbcf8daa0e6227b308df075b3802da92b1c");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cancel_action_hashing() -> Result<()> {
         let wallet = get_wallet()?;
         let action = Actions::Cancel(BulkCancel {
             cancels: vec![CancelRequest {
@@ -605,14 +1750,410 @@ mod tests {
                 oid: 82382,
             }],
         });
-        let connection_id = action.hash(1583838, None)?;
+        let connection_id = action.hash(1583838, None, None)?;
 
-        let signature = sign_l1_action(&wallet, connection_id, true)?;
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
         assert_eq!(signature.to_string(), "02f76cc5b16e0810152fa0e14e7b219f49c361e3325f771544c6f54e157bf9fa17ed0afc11a98596be85d5cd9f86600aad515337318f7ab346e5ccc1b03425d51b");
 
-        let signature = sign_l1_action(&wallet, connection_id, false)?;
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
         assert_eq!(signature.to_string(), "6ffebadfd48067663390962539fbde76cfa36f53be65abe2ab72c9db6d0db44457720db9d7c4860f142a484f070c84eb4b9694c3a617c83f0d698a27e55fd5e01c");
 
         Ok(())
     }
+
+    #[test]
+    fn test_action_weight_and_idempotency() {
+        let order = |cloid: Option<String>| OrderRequest {
+            asset: 1,
+            is_buy: true,
+            limit_px: "2000.0".to_string(),
+            sz: "3.5".to_string(),
+            reduce_only: false,
+            order_type: Order::Limit(Limit { tif: Tif::Gtc }),
+            cloid,
+        };
+        let bulk_order = |orders: Vec<OrderRequest>| {
+            Actions::Order(BulkOrder {
+                orders,
+                grouping: Grouping::Na,
+                builder: None,
+            })
+        };
+
+        let cloid = Some(uuid_to_hex_string(uuid::Uuid::new_v4()));
+        assert_eq!(bulk_order(vec![order(None); 39]).weight(), 1);
+        assert_eq!(bulk_order(vec![order(None); 80]).weight(), 3);
+        assert!(bulk_order(vec![order(cloid.clone()); 2]).is_idempotent());
+        assert!(!bulk_order(vec![order(cloid), order(None)]).is_idempotent());
+        assert!(Actions::ScheduleCancel(ScheduleCancel { time: None }).is_idempotent());
+    }
+
+    #[tokio::test]
+    async fn test_slippage_price() -> Result<()> {
+        let transport = Arc::new(MockTransport::new(|url_path, body| {
+            match (url_path, body["type"].as_str()) {
+                ("/info", Some("allMids")) => Ok(json!({"BTC": "50000.5"})),
+                ("/info", Some("meta")) => Ok(json!({"universe": [
+                    {"name": "BTC", "szDecimals": 5},
+                    {"name": "ETH", "szDecimals": 4}
+                ]})),
+                ("/info", Some("spotMeta")) => Ok(json!({"universe": [], "tokens": []})),
+                ("/info", Some("l2Book")) => Ok(json!({
+                    "coin": "ETH",
+                    "time": 0,
+                    "levels": [
+                        [{"px": "1799.9", "sz": "1", "n": 1}],
+                        [{"px": "1800.3", "sz": "1", "n": 1}]
+                    ]
+                })),
+                _ => Err(Error::GenericRequest(format!("unexpected request {body}"))),
+            }
+        }));
+        let exchange_client =
+            mock_exchange_client(transport.clone(), get_wallet()?, BaseUrl::Testnet).await?;
+
+        // Rounded to 5 significant figures
+        assert_eq!(
+            exchange_client.slippage_price("BTC", true, None).await?,
+            52501.0
+        );
+        assert_eq!(
+            exchange_client
+                .slippage_price("BTC", false, Some(0.01))
+                .await?,
+            49500.0
+        );
+        assert!(transport
+            .requests()
+            .iter()
+            .all(|(_, body)| body["type"] != "l2Book"));
+
+        // Not in allMids, so priced from the mid of the book
+        assert_eq!(
+            exchange_client.slippage_price("ETH", true, None).await?,
+            1890.1
+        );
+        assert_eq!(
+            exchange_client.slippage_price("ETH", false, None).await?,
+            1710.1
+        );
+        let (_, body) = transport.requests().pop().unwrap_or_default();
+        assert_eq!(body, json!({"type": "l2Book", "coin": "ETH"}));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_exchange_client_expires_after() -> Result<()> {
+        let transport = mock_api();
+        let mut exchange_client =
+            mock_exchange_client(transport.clone(), get_wallet()?, BaseUrl::Testnet).await?;
+        let order = || ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px: 1800.0,
+            sz: 0.01,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+        };
+
+        exchange_client.order(order(), None, None).await?;
+        let (_, body) = transport.requests().pop().unwrap_or_default();
+        assert!(body.get("expiresAfter").is_none());
+
+        exchange_client.expires_after = Some(1700000000000);
+        exchange_client.order(order(), None, None).await?;
+        let (_, body) = transport.requests().pop().unwrap_or_default();
+        assert_eq!(body["expiresAfter"], 1700000000000u64);
+
+        let options = ActionOptions {
+            expires_after: Some(1700000005000),
+            ..ActionOptions::default()
+        };
+        exchange_client.order(order(), Some(options), None).await?;
+        let (_, body) = transport.requests().pop().unwrap_or_default();
+        assert_eq!(body["expiresAfter"], 1700000005000u64);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_exchange_client_user_signed_chain() -> Result<()> {
+        let transport = mock_api();
+        let mut exchange_client =
+            mock_exchange_client(transport.clone(), get_wallet()?, BaseUrl::Mainnet).await?;
+        let destination = "0x0D1d9635D0640821d15e323ac8AdADfA9c111414";
+
+        exchange_client
+            .usdc_transfer("1", destination, None)
+            .await?;
+        let (_, body) = transport.requests().pop().unwrap_or_default();
+        assert_eq!(body["action"]["signatureChainId"], "0x66eee");
+        assert_eq!(body["action"]["hyperliquidChain"], "Mainnet");
+
+        exchange_client.signature_chain_id = ARBITRUM_ONE_CHAIN_ID.into();
+        exchange_client
+            .usdc_transfer("1", destination, None)
+            .await?;
+        let (_, body) = transport.requests().pop().unwrap_or_default();
+        assert_eq!(body["action"]["signatureChainId"], "0xa4b1");
+        assert_eq!(body["action"]["hyperliquidChain"], "Mainnet");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_exchange_client_per_call_vault() -> Result<()> {
+        let transport = mock_api();
+        let default_vault = H160::from_low_u64_be(1);
+        let other_vault = H160::from_low_u64_be(2);
+        let mut exchange_client =
+            mock_exchange_client(transport.clone(), get_wallet()?, BaseUrl::Testnet).await?;
+        exchange_client.vault_address = Some(default_vault);
+        let cancel = || ClientCancelRequest {
+            asset: "ETH".to_string(),
+            oid: 7,
+        };
+
+        exchange_client.cancel(cancel(), None, None).await?;
+        let (_, body) = transport.requests().pop().unwrap_or_default();
+        assert_eq!(body["vaultAddress"], json!(default_vault));
+
+        let options = ActionOptions {
+            vault_address: Some(other_vault),
+            ..ActionOptions::default()
+        };
+        exchange_client
+            .cancel(cancel(), Some(options), None)
+            .await?;
+        let (_, body) = transport.requests().pop().unwrap_or_default();
+        assert_eq!(body["vaultAddress"], json!(other_vault));
+
+        // The vault of a vault transfer is its target, not the account it's sent for
+        exchange_client
+            .vault_transfer(true, "1".to_string(), Some(other_vault), None, None)
+            .await?;
+        let (_, body) = transport.requests().pop().unwrap_or_default();
+        assert_eq!(body["action"]["vaultAddress"], json!(other_vault));
+        assert_eq!(body["vaultAddress"], json!(null));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sub_account_client_rejects_other_vaults() -> Result<()> {
+        let transport = mock_api();
+        let sub_account_user = H160::from_low_u64_be(1);
+        let other_vault = H160::from_low_u64_be(2);
+        let exchange_client =
+            mock_exchange_client(transport.clone(), get_wallet()?, BaseUrl::Testnet).await?;
+        let sub_account = exchange_client.sub_account(sub_account_user);
+        let cancel = || ClientCancelRequest {
+            asset: "ETH".to_string(),
+            oid: 7,
+        };
+        let options = |vault_address| ActionOptions {
+            vault_address: Some(vault_address),
+            ..ActionOptions::default()
+        };
+
+        let sent = transport.requests().len();
+        let res = sub_account
+            .cancel(cancel(), Some(options(other_vault)), None)
+            .await;
+        assert!(matches!(
+            res,
+            Err(Error::VaultAddressMismatch { expected, requested })
+                if expected == sub_account_user && requested == other_vault
+        ));
+        assert_eq!(transport.requests().len(), sent);
+
+        sub_account
+            .cancel(cancel(), Some(options(sub_account_user)), None)
+            .await?;
+        let (_, body) = transport.requests().pop().unwrap_or_default();
+        assert_eq!(body["vaultAddress"], json!(sub_account_user));
+        Ok(())
+    }
+
+    /// Signs with a different key than the address it reports, like an agent key loaded for
+    /// the wrong account
+    struct MisconfiguredSigner {
+        address: H160,
+        wallet: LocalWallet,
+    }
+
+    #[async_trait]
+    impl HyperliquidSigner for MisconfiguredSigner {
+        fn address(&self) -> H160 {
+            self.address
+        }
+
+        async fn sign_hash(&self, hash: H256) -> Result<Signature> {
+            HyperliquidSigner::sign_hash(&self.wallet, hash).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exchange_client_verifies_signatures() -> Result<()> {
+        let transport = mock_api();
+        let signer = MisconfiguredSigner {
+            address: H160::from_low_u64_be(1),
+            wallet: get_wallet()?,
+        };
+        let mut exchange_client =
+            mock_exchange_client(transport.clone(), signer, BaseUrl::Testnet).await?;
+        let cancel = || ClientCancelRequest {
+            asset: "ETH".to_string(),
+            oid: 7,
+        };
+
+        exchange_client.cancel(cancel(), None, None).await?;
+        let sent = transport.requests().len();
+
+        exchange_client.verify_signatures = true;
+        let res = exchange_client.cancel(cancel(), None, None).await;
+        assert!(
+            matches!(res, Err(Error::SignerMismatch { expected, .. }) if expected == H160::from_low_u64_be(1))
+        );
+        let res = exchange_client
+            .usdc_transfer("1", "0x0D1d9635D0640821d15e323ac8AdADfA9c111414", None)
+            .await;
+        assert!(matches!(res, Err(Error::SignerMismatch { .. })));
+        assert_eq!(transport.requests().len(), sent);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_exchange_client_dry_run() -> Result<()> {
+        let transport = mock_api();
+        let mut exchange_client =
+            mock_exchange_client(transport.clone(), get_wallet()?, BaseUrl::Mainnet).await?;
+        exchange_client.dry_run = true;
+
+        let order = ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px: 1800.0,
+            sz: 0.01,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+        };
+        let response = exchange_client.order(order, None, None).await?;
+        let ExchangeResponseStatus::Ok(response) = response else {
+            panic!("expected ok response");
+        };
+        let statuses = response.data.map(|data| data.statuses).unwrap_or_default();
+        assert!(matches!(&statuses[..], [ExchangeDataStatus::Resting(_)]));
+
+        // Metadata is still fetched, actions aren't sent
+        assert!(transport
+            .requests()
+            .iter()
+            .all(|(url_path, _)| url_path == "/info"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_market_close_position_of_user() -> Result<()> {
+        let user = H160::from_low_u64_be(9);
+        let transport = Arc::new(MockTransport::new(move |url_path, body| {
+            let margin_summary = json!({
+                "accountValue": "1000.0",
+                "totalMarginUsed": "0.0",
+                "totalNtlPos": "0.0",
+                "totalRawUsd": "1000.0"
+            });
+            match (url_path, body["type"].as_str()) {
+                ("/info", Some("allMids")) => Ok(json!({"ETH": "1800.0"})),
+                ("/info", Some("meta")) => {
+                    Ok(json!({"universe": [{"name": "ETH", "szDecimals": 4}]}))
+                }
+                ("/info", Some("spotMeta")) => Ok(json!({"universe": [], "tokens": []})),
+                ("/info", Some("clearinghouseState")) => {
+                    let asset_positions = if body["user"] == json!(user) {
+                        json!([{"type": "oneWay", "position": {
+                            "coin": "ETH",
+                            "entryPx": "1800.0",
+                            "leverage": {"type": "cross", "value": 20, "rawUsd": null},
+                            "liquidationPx": null,
+                            "marginUsed": "45.0",
+                            "positionValue": "900.0",
+                            "returnOnEquity": "0.0",
+                            "szi": "-0.5",
+                            "unrealizedPnl": "0.0"
+                        }}])
+                    } else {
+                        json!([])
+                    };
+                    Ok(json!({
+                        "assetPositions": asset_positions,
+                        "marginSummary": margin_summary,
+                        "crossMarginSummary": margin_summary,
+                        "withdrawable": "1000.0"
+                    }))
+                }
+                ("/exchange", _) => Ok(json!({
+                    "status": "ok",
+                    "response": {"type": "order", "data": {"statuses": [{"resting": {"oid": 7}}]}}
+                })),
+                _ => Err(Error::GenericRequest(format!("unexpected request {body}"))),
+            }
+        }));
+        // Signs as an agent of `user`, which holds no positions itself
+        let exchange_client =
+            mock_exchange_client(transport.clone(), get_wallet()?, BaseUrl::Testnet).await?;
+
+        let res = exchange_client
+            .market_close("ETH", None, None, None, None, None)
+            .await;
+        assert!(matches!(res, Err(Error::PositionNotFound)));
+
+        exchange_client
+            .market_close("ETH", None, None, Some(user), None, None)
+            .await?;
+        let requests = transport.requests();
+        let (url_path, body) = requests.last().unwrap();
+        assert_eq!(url_path, "/exchange");
+        let order = &body["action"]["orders"][0];
+        assert_eq!(order["b"], json!(true));
+        assert_eq!(order["r"], json!(true));
+        assert_eq!(order["s"], json!("0.5"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_exchange_request_errors_keep_their_type() -> Result<()> {
+        let transport = Arc::new(MockTransport::new(|url_path, body| {
+            match (url_path, body["type"].as_str()) {
+                ("/info", Some("meta")) => {
+                    Ok(json!({"universe": [{"name": "ETH", "szDecimals": 4}]}))
+                }
+                ("/info", Some("spotMeta")) => Ok(json!({"universe": [], "tokens": []})),
+                _ => Err(Error::ServerRequest {
+                    status_code: 502,
+                    error_message: "bad gateway".to_string(),
+                }),
+            }
+        }));
+        let exchange_client =
+            mock_exchange_client(transport, get_wallet()?, BaseUrl::Testnet).await?;
+
+        let order = ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px: 2000.0,
+            sz: 0.1,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+        };
+        let res = exchange_client.order(order, None, None).await;
+        assert!(matches!(
+            res,
+            Err(Error::ServerRequest {
+                status_code: 502,
+                ..
+            })
+        ));
+        Ok(())
+    }
 }
@@ -0,0 +1,196 @@
+use serde::Deserialize;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExchangeErrorKind {
+    /// Not enough margin, or spot balance, to place the order
+    InsufficientMargin,
+    /// Price isn't a multiple of the tick size or has too many significant figures
+    TickSize,
+    /// Size has more decimals than the asset allows or is zero
+    InvalidSize,
+    /// Order value is below the exchange minimum
+    MinNotional,
+    /// Reduce only order would increase the position
+    ReduceOnly,
+    /// Post only (Alo) order would have matched immediately
+    PostOnlyWouldCross,
+    /// Ioc order found nothing to match against
+    NoImmediateMatch,
+    /// Order to cancel or modify was never placed, already canceled or filled
+    OrderNotFound,
+    /// Nonce was already used or is outside the accepted window
+    InvalidNonce,
+    RateLimited,
+    Unknown,
+}
+
+impl ExchangeErrorKind {
+    /// Whether sending the request again, re-signed with a fresh nonce, may succeed without
+    /// changing it. Other errors need the request or the account to change first.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ExchangeErrorKind::PostOnlyWouldCross
+                | ExchangeErrorKind::NoImmediateMatch
+                | ExchangeErrorKind::InvalidNonce
+                | ExchangeErrorKind::RateLimited
+        )
+    }
+
+    pub fn is_fatal(&self) -> bool {
+        !self.is_retryable()
+    }
+
+    fn classify(message: &str) -> ExchangeErrorKind {
+        let message = message.to_lowercase();
+        let contains_any = |patterns: &[&str]| patterns.iter().any(|p| message.contains(p));
+
+        if contains_any(&["insufficient margin", "insufficient spot balance"]) {
+            ExchangeErrorKind::InsufficientMargin
+        } else if contains_any(&["tick size", "invalid price"]) {
+            ExchangeErrorKind::TickSize
+        } else if contains_any(&["invalid size", "zero size"]) {
+            ExchangeErrorKind::InvalidSize
+        } else if message.contains("minimum value") {
+            ExchangeErrorKind::MinNotional
+        } else if message.contains("reduce only") {
+            ExchangeErrorKind::ReduceOnly
+        } else if message.contains("post only") {
+            ExchangeErrorKind::PostOnlyWouldCross
+        } else if message.contains("could not immediately match") {
+            ExchangeErrorKind::NoImmediateMatch
+        } else if contains_any(&["never placed", "already canceled", "order not found"]) {
+            ExchangeErrorKind::OrderNotFound
+        } else if message.contains("nonce") {
+            ExchangeErrorKind::InvalidNonce
+        } else if contains_any(&["too many", "rate limit"]) {
+            ExchangeErrorKind::RateLimited
+        } else {
+            ExchangeErrorKind::Unknown
+        }
+    }
+}
+
+/// Error message returned by the exchange for a request or a single order, classified by kind
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(from = "String")]
+pub struct ExchangeError {
+    pub kind: ExchangeErrorKind,
+    pub message: String,
+}
+
+impl ExchangeError {
+    pub fn is_retryable(&self) -> bool {
+        self.kind.is_retryable()
+    }
+
+    pub fn is_fatal(&self) -> bool {
+        self.kind.is_fatal()
+    }
+}
+
+impl From<String> for ExchangeError {
+    fn from(message: String) -> ExchangeError {
+        ExchangeError {
+            kind: ExchangeErrorKind::classify(&message),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for ExchangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, Error, ExchangeDataStatus, ExchangeResponseStatus};
+
+    #[test]
+    fn test_classify_exchange_errors() {
+        let cases = [
+            (
+                "Insufficient margin to place order. asset=4",
+                ExchangeErrorKind::InsufficientMargin,
+            ),
+            (
+                "Insufficient spot balance asset=10000",
+                ExchangeErrorKind::InsufficientMargin,
+            ),
+            (
+                "Price must be divisible by tick size. asset=4",
+                ExchangeErrorKind::TickSize,
+            ),
+            ("Order has invalid size.", ExchangeErrorKind::InvalidSize),
+            (
+                "Order must have minimum value of $10. asset=4",
+                ExchangeErrorKind::MinNotional,
+            ),
+            (
+                "Reduce only order would increase position. asset=4",
+                ExchangeErrorKind::ReduceOnly,
+            ),
+            (
+                "Post only order would have immediately matched, bbo was 2000.1@2000.2. asset=4",
+                ExchangeErrorKind::PostOnlyWouldCross,
+            ),
+            (
+                "Order could not immediately match against any resting orders. asset=4",
+                ExchangeErrorKind::NoImmediateMatch,
+            ),
+            (
+                "Order was never placed, already canceled, or filled. asset=4",
+                ExchangeErrorKind::OrderNotFound,
+            ),
+            (
+                "Invalid nonce: duplicate nonce 1583838",
+                ExchangeErrorKind::InvalidNonce,
+            ),
+            (
+                "Too many cumulative requests sent (10001 > 10000) for cumulative volume traded $0.",
+                ExchangeErrorKind::RateLimited,
+            ),
+            ("Vault not registered", ExchangeErrorKind::Unknown),
+        ];
+
+        for (message, kind) in cases {
+            let error = ExchangeError::from(message.to_string());
+            assert_eq!(error.kind, kind, "{message}");
+            assert_eq!(error.to_string(), message);
+        }
+        assert!(ExchangeErrorKind::RateLimited.is_retryable());
+        assert!(ExchangeErrorKind::InsufficientMargin.is_fatal());
+    }
+
+    #[test]
+    fn test_deserialize_exchange_errors() -> Result<()> {
+        let response: ExchangeResponseStatus = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"order","data":{"statuses":[{"error":"Order must have minimum value of $10. asset=4"}]}}}"#,
+        )
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let ExchangeResponseStatus::Ok(response) = response else {
+            panic!("expected ok response");
+        };
+        let statuses = response.data.map(|data| data.statuses).unwrap_or_default();
+        assert!(matches!(
+            &statuses[..],
+            [ExchangeDataStatus::Error(ExchangeError {
+                kind: ExchangeErrorKind::MinNotional,
+                ..
+            })]
+        ));
+
+        let response: ExchangeResponseStatus =
+            serde_json::from_str(r#"{"status":"err","response":"Too many requests"}"#)
+                .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let ExchangeResponseStatus::Err(error) = response else {
+            panic!("expected error response");
+        };
+        assert!(error.is_retryable());
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+use crate::ExchangeError;
+use ethers::types::H160;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug, Clone)]
@@ -13,13 +15,19 @@ pub struct FilledOrder {
     pub oid: u64,
 }
 
+#[cfg(feature = "rust_decimal")]
+crate::number::decimal_accessors!(FilledOrder {
+    total_sz_decimal: total_sz,
+    avg_px_decimal: avg_px,
+});
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum ExchangeDataStatus {
     Success,
     WaitingForFill,
     WaitingForTrigger,
-    Error(String),
+    Error(ExchangeError),
     Resting(RestingOrder),
     Filled(FilledOrder),
 }
@@ -39,7 +47,48 @@ pub struct ExchangeResponse {
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 #[serde(tag = "status", content = "response")]
-pub enum ExchangeResponseStatus {
-    Ok(ExchangeResponse),
-    Err(String),
+pub enum ExchangeResponseStatus<T = ExchangeResponse> {
+    Ok(T),
+    Err(ExchangeError),
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CreateSubAccountResponse {
+    #[serde(rename = "type")]
+    pub response_type: String,
+    #[serde(rename = "data")]
+    pub sub_account_user: H160,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningTwap {
+    pub twap_id: u64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum TwapStatus {
+    Running(RunningTwap),
+    Success,
+    Error(ExchangeError),
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TwapResponseData {
+    pub status: TwapStatus,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TwapResponse {
+    #[serde(rename = "type")]
+    pub response_type: String,
+    pub data: TwapResponseData,
+}
+
+#[derive(Debug, Clone)]
+pub struct BracketOrderStatuses {
+    pub entry: ExchangeDataStatus,
+    pub take_profit: Option<ExchangeDataStatus>,
+    pub stop_loss: Option<ExchangeDataStatus>,
 }
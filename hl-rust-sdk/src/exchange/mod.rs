@@ -1,11 +1,34 @@
 mod actions;
+mod agent;
 mod cancel;
+#[cfg(feature = "rust_decimal")]
+mod decimal;
+mod dry_run;
 mod exchange_client;
+mod exchange_error;
 mod exchange_responses;
+mod modify;
+mod normalizer;
 mod order;
+mod payload;
+mod sub_account;
+mod twap;
 
 pub use actions::*;
+pub use agent::AgentCredentials;
 pub use cancel::{ClientCancelRequest, ClientCancelRequestCloid};
+#[cfg(feature = "rust_decimal")]
+pub use decimal::{
+    ClientOrderDecimal, ClientOrderRequestDecimal, ClientTriggerDecimal, ClientTwapRequestDecimal,
+};
 pub use exchange_client::*;
+pub use exchange_error::{ExchangeError, ExchangeErrorKind};
 pub use exchange_responses::*;
-pub use order::{ClientLimit, ClientOrder, ClientOrderRequest, ClientTrigger, Order};
+pub use modify::{ClientModifyRequest, ClientModifyRequestCloid};
+pub(crate) use modify::OidOrCloid;
+pub use normalizer::{NormalizationMode, OrderNormalizer};
+pub use order::{ClientLimit, ClientOrder, ClientOrderRequest, ClientTrigger, Order, Tif, TpSl};
+pub(crate) use order::OrderRequest;
+pub use payload::{ExchangePayload, ExchangePayloadBuilder};
+pub use sub_account::SubAccountClient;
+pub use twap::ClientTwapRequest;
@@ -0,0 +1,26 @@
+use crate::exchange::{order::OrderRequest, ClientOrderRequest};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub struct ClientModifyRequest {
+    pub oid: u64,
+    pub order: ClientOrderRequest,
+}
+
+pub struct ClientModifyRequestCloid {
+    pub cloid: Uuid,
+    pub order: ClientOrderRequest,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum OidOrCloid {
+    Oid(u64),
+    Cloid(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModifyRequest {
+    pub oid: OidOrCloid,
+    pub order: OrderRequest,
+}
@@ -0,0 +1,273 @@
+use crate::{
+    consts::{EPSILON, MAX_DECIMALS_PERP, MAX_DECIMALS_SPOT, MAX_SIGNIFICANT_FIGURES},
+    exchange::{ClientOrder, ClientOrderRequest, ClientTrigger},
+    helpers::round_to_decimals,
+    meta::{Meta, SpotMeta},
+    prelude::*,
+    Error,
+};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalizationMode {
+    /// Reject prices and sizes that the exchange would reject
+    Strict,
+    /// Round prices to the nearest valid tick and sizes down to the allowed decimals
+    Lenient,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct AssetDecimals {
+    sz_decimals: u32,
+    max_px_decimals: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OrderNormalizer {
+    decimals: HashMap<String, AssetDecimals>,
+}
+
+impl OrderNormalizer {
+    pub fn new(meta: &Meta, spot_meta: &SpotMeta) -> OrderNormalizer {
+        let mut decimals = HashMap::new();
+        for asset in meta.universe.iter() {
+            decimals.insert(
+                asset.name.clone(),
+                AssetDecimals {
+                    sz_decimals: asset.sz_decimals,
+                    max_px_decimals: MAX_DECIMALS_PERP,
+                },
+            );
+        }
+
+        let index_to_token: HashMap<usize, _> = spot_meta
+            .tokens
+            .iter()
+            .map(|info| (info.index, info))
+            .collect();
+
+        for asset in spot_meta.universe.iter() {
+            let (Some(base), Some(quote)) = (
+                index_to_token.get(&asset.tokens[0]),
+                index_to_token.get(&asset.tokens[1]),
+            ) else {
+                continue;
+            };
+
+            // Spot sizes are denominated in the base token
+            let asset_decimals = AssetDecimals {
+                sz_decimals: base.sz_decimals.into(),
+                max_px_decimals: MAX_DECIMALS_SPOT,
+            };
+            decimals.insert(format!("{}/{}", base.name, quote.name), asset_decimals);
+            decimals.insert(asset.name.clone(), asset_decimals);
+        }
+
+        OrderNormalizer { decimals }
+    }
+
+    fn asset_decimals(&self, coin: &str) -> Result<AssetDecimals> {
+        self.decimals.get(coin).copied().ok_or(Error::AssetNotFound)
+    }
+
+    pub fn sz_decimals(&self, coin: &str) -> Result<u32> {
+        Ok(self.asset_decimals(coin)?.sz_decimals)
+    }
+
+    /// Number of decimals allowed for a price of this magnitude: at most 5 significant figures
+    /// and at most `6 - szDecimals` (perps) or `8 - szDecimals` (spot) decimals.
+    /// Integer prices are always allowed.
+    pub fn px_decimals(&self, coin: &str, px: f64) -> Result<u32> {
+        let magnitude = (px.abs() >= EPSILON).then(|| px.abs().log10().floor() as i32);
+        self.px_decimals_of_magnitude(coin, magnitude)
+    }
+
+    /// `px_decimals` of a price whose leading digit is at 10^`magnitude`, or of a zero price
+    pub(super) fn px_decimals_of_magnitude(
+        &self,
+        coin: &str,
+        magnitude: Option<i32>,
+    ) -> Result<u32> {
+        let AssetDecimals {
+            sz_decimals,
+            max_px_decimals,
+        } = self.asset_decimals(coin)?;
+        let max_decimals = max_px_decimals.saturating_sub(sz_decimals);
+        let Some(magnitude) = magnitude else {
+            return Ok(max_decimals);
+        };
+
+        let significant_decimals = MAX_SIGNIFICANT_FIGURES - magnitude - 1;
+        Ok((significant_decimals.max(0) as u32).min(max_decimals))
+    }
+
+    pub fn tick_size(&self, coin: &str, px: f64) -> Result<f64> {
+        Ok(10f64.powi(-(self.px_decimals(coin, px)? as i32)))
+    }
+
+    pub fn round_px(&self, coin: &str, px: f64) -> Result<f64> {
+        Ok(round_to_decimals(px, self.px_decimals(coin, px)?))
+    }
+
+    /// Rounds the price and moves it by `ticks` valid price increments
+    pub fn offset_px(&self, coin: &str, px: f64, ticks: i32) -> Result<f64> {
+        let px = self.round_px(coin, px)?;
+        self.round_px(coin, px + ticks as f64 * self.tick_size(coin, px)?)
+    }
+
+    pub fn round_sz(&self, coin: &str, sz: f64) -> Result<f64> {
+        let pow10 = 10f64.powi(self.sz_decimals(coin)? as i32);
+        // Nudge by epsilon so that sizes like 0.3 aren't floored to 0.2999
+        Ok((sz * pow10 + EPSILON).floor() / pow10)
+    }
+
+    pub fn normalize_px(&self, coin: &str, px: f64, mode: NormalizationMode) -> Result<f64> {
+        let rounded = self.round_px(coin, px)?;
+        if mode == NormalizationMode::Strict && !is_close(rounded, px) {
+            return Err(Error::InvalidPrice {
+                coin: coin.to_string(),
+                px,
+            });
+        }
+        Ok(rounded)
+    }
+
+    pub fn normalize_sz(&self, coin: &str, sz: f64, mode: NormalizationMode) -> Result<f64> {
+        let rounded = self.round_sz(coin, sz)?;
+        if mode == NormalizationMode::Strict && !is_close(rounded, sz) {
+            return Err(Error::InvalidSize {
+                coin: coin.to_string(),
+                sz,
+            });
+        }
+        Ok(rounded)
+    }
+
+    pub fn normalize_order(
+        &self,
+        order: ClientOrderRequest,
+        mode: NormalizationMode,
+    ) -> Result<ClientOrderRequest> {
+        let order_type = match order.order_type {
+            ClientOrder::Trigger(trigger) => ClientOrder::Trigger(ClientTrigger {
+                trigger_px: self.normalize_px(&order.asset, trigger.trigger_px, mode)?,
+                ..trigger
+            }),
+            limit => limit,
+        };
+
+        Ok(ClientOrderRequest {
+            limit_px: self.normalize_px(&order.asset, order.limit_px, mode)?,
+            sz: self.normalize_sz(&order.asset, order.sz, mode)?,
+            order_type,
+            ..order
+        })
+    }
+}
+
+fn is_close(x: f64, y: f64) -> bool {
+    (x - y).abs() <= EPSILON * x.abs().max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AssetMeta, ClientLimit, SpotAssetMeta, Tif, TokenInfo};
+    use ethers::types::H128;
+
+    fn get_normalizer() -> OrderNormalizer {
+        let meta = Meta {
+            universe: vec![
+                AssetMeta {
+                    name: "BTC".to_string(),
+                    sz_decimals: 5,
+                },
+                AssetMeta {
+                    name: "DOGE".to_string(),
+                    sz_decimals: 0,
+                },
+            ],
+        };
+        let token = |name: &str, sz_decimals: u8, index: usize| TokenInfo {
+            name: name.to_string(),
+            sz_decimals,
+            wei_decimals: 8,
+            index,
+            token_id: H128::zero(),
+            is_canonical: true,
+        };
+        let spot_meta = SpotMeta {
+            universe: vec![SpotAssetMeta {
+                tokens: [1, 0],
+                name: "@1".to_string(),
+                index: 1,
+                is_canonical: false,
+            }],
+            tokens: vec![token("USDC", 8, 0), token("PURR", 0, 1)],
+        };
+        OrderNormalizer::new(&meta, &spot_meta)
+    }
+
+    #[test]
+    fn test_px_decimals() -> Result<()> {
+        let normalizer = get_normalizer();
+        assert_eq!(normalizer.px_decimals("BTC", 65432.1)?, 0);
+        assert_eq!(normalizer.px_decimals("BTC", 123456.0)?, 0);
+        assert_eq!(normalizer.px_decimals("DOGE", 0.123456)?, 5);
+        assert_eq!(normalizer.px_decimals("DOGE", 0.0123456)?, 6);
+        assert_eq!(normalizer.px_decimals("PURR/USDC", 0.000123456)?, 8);
+        assert_eq!(normalizer.px_decimals("@1", 0.000123456)?, 8);
+        assert!(normalizer.px_decimals("ETH", 1.0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_px_and_sz() -> Result<()> {
+        let normalizer = get_normalizer();
+        let lenient = NormalizationMode::Lenient;
+        let strict = NormalizationMode::Strict;
+
+        assert_eq!(normalizer.normalize_px("BTC", 65432.1, lenient)?, 65432.0);
+        assert!(normalizer.normalize_px("BTC", 65432.1, strict).is_err());
+        assert_eq!(normalizer.normalize_px("BTC", 65432.0, strict)?, 65432.0);
+        assert_eq!(
+            normalizer.normalize_px("DOGE", 0.1234567, lenient)?,
+            0.12346
+        );
+        assert_eq!(normalizer.normalize_px("DOGE", 0.12346, strict)?, 0.12346);
+
+        assert_eq!(normalizer.normalize_sz("BTC", 0.123456, lenient)?, 0.12345);
+        assert!(normalizer.normalize_sz("BTC", 0.123456, strict).is_err());
+        assert_eq!(normalizer.normalize_sz("PURR/USDC", 2.7, lenient)?, 2.0);
+        assert_eq!(normalizer.normalize_sz("DOGE", 10.9, lenient)?, 10.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_offset_px() -> Result<()> {
+        let normalizer = get_normalizer();
+        assert_eq!(normalizer.offset_px("DOGE", 0.12346, -1)?, 0.12345);
+        assert_eq!(normalizer.offset_px("DOGE", 0.12346, 1)?, 0.12347);
+        assert_eq!(normalizer.offset_px("BTC", 65432.4, 1)?, 65433.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_order() -> Result<()> {
+        let normalizer = get_normalizer();
+        let order = ClientOrderRequest {
+            asset: "DOGE".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px: 0.1234567,
+            sz: 100.5,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+        };
+
+        let order = normalizer.normalize_order(order, NormalizationMode::Lenient)?;
+        assert_eq!(order.limit_px, 0.12346);
+        assert_eq!(order.sz, 100.0);
+        Ok(())
+    }
+}
@@ -7,9 +7,24 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tif {
+    Alo,
+    Ioc,
+    Gtc,
+    FrontendMarket,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TpSl {
+    Tp,
+    Sl,
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Limit {
-    pub tif: String,
+    pub tif: Tif,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -17,7 +32,7 @@ pub struct Limit {
 pub struct Trigger {
     pub is_market: bool,
     pub trigger_px: String,
-    pub tpsl: String,
+    pub tpsl: TpSl,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -47,13 +62,13 @@ pub struct OrderRequest {
 }
 
 pub struct ClientLimit {
-    pub tif: String,
+    pub tif: Tif,
 }
 
 pub struct ClientTrigger {
     pub is_market: bool,
     pub trigger_px: f64,
-    pub tpsl: String,
+    pub tpsl: TpSl,
 }
 
 pub enum ClientOrder {
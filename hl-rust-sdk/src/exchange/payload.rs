@@ -0,0 +1,185 @@
+use ethers::{
+    signers::LocalWallet,
+    types::{Signature, H160},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    nonce::shared_nonce_manager,
+    prelude::*,
+    signature::{sign_l1_action, sign_typed_data},
+    Actions, Error, HyperliquidSigner,
+};
+
+/// Body of a request to the `/exchange` endpoint
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangePayload {
+    pub action: serde_json::Value,
+    pub signature: Signature,
+    pub nonce: u64,
+    pub vault_address: Option<H160>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_after: Option<u64>,
+}
+
+/// Signs actions into an `ExchangePayload` without touching the network, so that they can be
+/// prepared on one machine and sent with `ExchangeClient::submit_signed_payload` from another.
+pub struct ExchangePayloadBuilder<'a, S: HyperliquidSigner = LocalWallet> {
+    pub signer: &'a S,
+    pub is_mainnet: bool,
+    pub vault_address: Option<H160>,
+    /// Nonce for L1 actions, defaults to the next nonce of the signer. User signed actions
+    /// (`UsdSend`, `Withdraw3`, `SpotSend`, `ApproveAgent`, `ApproveBuilderFee`) always use
+    /// the time or nonce they carry.
+    pub nonce: Option<u64>,
+    /// Time (ms since epoch) after which the exchange rejects the L1 action
+    pub expires_after: Option<u64>,
+}
+
+impl<'a, S: HyperliquidSigner> ExchangePayloadBuilder<'a, S> {
+    pub fn new(signer: &'a S, is_mainnet: bool) -> ExchangePayloadBuilder<'a, S> {
+        ExchangePayloadBuilder {
+            signer,
+            is_mainnet,
+            vault_address: None,
+            nonce: None,
+            expires_after: None,
+        }
+    }
+
+    pub fn vault_address(self, vault_address: Option<H160>) -> ExchangePayloadBuilder<'a, S> {
+        ExchangePayloadBuilder {
+            vault_address,
+            ..self
+        }
+    }
+
+    pub fn nonce(self, nonce: u64) -> ExchangePayloadBuilder<'a, S> {
+        ExchangePayloadBuilder {
+            nonce: Some(nonce),
+            ..self
+        }
+    }
+
+    pub fn expires_after(self, expires_after: Option<u64>) -> ExchangePayloadBuilder<'a, S> {
+        ExchangePayloadBuilder {
+            expires_after,
+            ..self
+        }
+    }
+
+    pub async fn build(&self, action: Actions) -> Result<ExchangePayload> {
+        // User signed actions aren't covered by the expiry
+        let mut expires_after = None;
+        let (signature, nonce) = match &action {
+            Actions::UsdSend(usd_send) => {
+                (sign_typed_data(usd_send, self.signer).await?, usd_send.time)
+            }
+            Actions::Withdraw3(withdraw) => {
+                (sign_typed_data(withdraw, self.signer).await?, withdraw.time)
+            }
+            Actions::SpotSend(spot_send) => (
+                sign_typed_data(spot_send, self.signer).await?,
+                spot_send.time,
+            ),
+            Actions::ApproveAgent(approve_agent) => (
+                sign_typed_data(approve_agent, self.signer).await?,
+                approve_agent.nonce,
+            ),
+            Actions::ApproveBuilderFee(approve_builder_fee) => (
+                sign_typed_data(approve_builder_fee, self.signer).await?,
+                approve_builder_fee.nonce,
+            ),
+            _ => {
+                let nonce = match self.nonce {
+                    Some(nonce) => nonce,
+                    None => shared_nonce_manager().next_nonce(self.signer.address())?,
+                };
+                expires_after = self.expires_after;
+                let connection_id = action.hash(nonce, self.vault_address, expires_after)?;
+                (
+                    sign_l1_action(self.signer, connection_id, self.is_mainnet).await?,
+                    nonce,
+                )
+            }
+        };
+
+        Ok(ExchangePayload {
+            action: serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+            signature,
+            nonce,
+            vault_address: self.vault_address,
+            expires_after,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ScheduleCancel, UsdSend};
+
+    fn get_wallet() -> Result<LocalWallet> {
+        let priv_key = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e";
+        priv_key
+            .parse::<LocalWallet>()
+            .map_err(|e| Error::Wallet(e.to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_build_l1_action_payload() -> Result<()> {
+        let wallet = get_wallet()?;
+        let payload = ExchangePayloadBuilder::new(&wallet, true)
+            .nonce(1583838)
+            .build(Actions::ScheduleCancel(ScheduleCancel {
+                time: Some(1583848),
+            }))
+            .await?;
+
+        assert_eq!(payload.nonce, 1583838);
+        assert_eq!(payload.signature.to_string(), "b41fe7bf990a0b17485cb1643d1444e1cf8286cd9e53cb31c4e04fe0dd595480388908c0112de78e5fe22ca7abcca89b9629390fa1ba7d29f158fca60ca9c3361b");
+        assert_eq!(
+            payload.action,
+            serde_json::json!({"type": "scheduleCancel", "time": 1583848})
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_build_expiring_l1_action_payload() -> Result<()> {
+        let wallet = get_wallet()?;
+        let payload = ExchangePayloadBuilder::new(&wallet, true)
+            .nonce(1583838)
+            .expires_after(Some(1593838))
+            .build(Actions::ScheduleCancel(ScheduleCancel {
+                time: Some(1583848),
+            }))
+            .await?;
+
+        assert_eq!(payload.expires_after, Some(1593838));
+        assert_eq!(payload.signature.to_string(), "e0fd28b95876cb31a395cae6d580f96b70043c3b0563f80d35ef31ae11adda07797bf636a89eec5185546a20ef0d3e602a0d48e8fd9c4b42a9aea2454940f2081c");
+        let body = serde_json::to_value(&payload).map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(body["expiresAfter"], 1593838);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_build_user_signed_action_payload() -> Result<()> {
+        let wallet = get_wallet()?;
+        let payload = ExchangePayloadBuilder::new(&wallet, false)
+            .nonce(1)
+            .build(Actions::UsdSend(UsdSend {
+                signature_chain_id: 421614.into(),
+                hyperliquid_chain: "Testnet".to_string(),
+                destination: "0x0D1d9635D0640821d15e323ac8AdADfA9c111414".to_string(),
+                amount: "1".to_string(),
+                time: 1690393044548,
+            }))
+            .await?;
+
+        assert_eq!(payload.nonce, 1690393044548);
+        assert_eq!(payload.signature.to_string(), "214d507bbdaebba52fa60928f904a8b2df73673e3baba6133d66fe846c7ef70451e82453a6d8db124e7ed6e60fa00d4b7c46e4d96cb2bd61fd81b6e8953cc9d21b");
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{
+    exchange::{
+        ActionOptions, ClientCancelRequest, ClientCancelRequestCloid, ClientModifyRequest,
+        ClientModifyRequestCloid, ClientOrderRequest, ExchangeClient, ExchangeResponseStatus,
+        Grouping,
+    },
+    prelude::*,
+    Error, HyperliquidSigner,
+};
+use ethers::{signers::LocalWallet, types::H160};
+
+/// Borrowed view of an `ExchangeClient` that signs trading actions on behalf of a sub-account
+pub struct SubAccountClient<'a, S: HyperliquidSigner = LocalWallet> {
+    pub exchange_client: &'a ExchangeClient<S>,
+    pub sub_account_user: H160,
+}
+
+impl<S: HyperliquidSigner> SubAccountClient<'_, S> {
+    /// The sub-account to act for, unless `options` asks for a different vault
+    fn vault(&self, options: Option<ActionOptions>) -> Result<Option<H160>> {
+        match options.and_then(|options| options.vault_address) {
+            Some(requested) if requested != self.sub_account_user => {
+                Err(Error::VaultAddressMismatch {
+                    expected: self.sub_account_user,
+                    requested,
+                })
+            }
+            _ => Ok(Some(self.sub_account_user)),
+        }
+    }
+
+    pub async fn order(
+        &self,
+        order: ClientOrderRequest,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.bulk_order(vec![order], options, wallet).await
+    }
+
+    pub async fn bulk_order(
+        &self,
+        orders: Vec<ClientOrderRequest>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.exchange_client
+            .bulk_order_with_grouping_for_vault(
+                orders,
+                Grouping::Na,
+                None,
+                self.vault(options)?,
+                options,
+                wallet,
+            )
+            .await
+    }
+
+    pub async fn cancel(
+        &self,
+        cancel: ClientCancelRequest,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.bulk_cancel(vec![cancel], options, wallet).await
+    }
+
+    pub async fn bulk_cancel(
+        &self,
+        cancels: Vec<ClientCancelRequest>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.exchange_client
+            .bulk_cancel_for_vault(cancels, self.vault(options)?, options, wallet)
+            .await
+    }
+
+    pub async fn bulk_cancel_by_cloid(
+        &self,
+        cancels: Vec<ClientCancelRequestCloid>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.exchange_client
+            .bulk_cancel_by_cloid_for_vault(cancels, self.vault(options)?, options, wallet)
+            .await
+    }
+
+    pub async fn bulk_modify(
+        &self,
+        modifies: Vec<ClientModifyRequest>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.exchange_client
+            .bulk_modify_for_vault(modifies, self.vault(options)?, options, wallet)
+            .await
+    }
+
+    pub async fn bulk_modify_by_cloid(
+        &self,
+        modifies: Vec<ClientModifyRequestCloid>,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.exchange_client
+            .bulk_modify_by_cloid_for_vault(modifies, self.vault(options)?, options, wallet)
+            .await
+    }
+
+    pub async fn update_leverage(
+        &self,
+        leverage: u32,
+        coin: &str,
+        is_cross: bool,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.exchange_client
+            .update_leverage_for_vault(
+                leverage,
+                coin,
+                is_cross,
+                self.vault(options)?,
+                options,
+                wallet,
+            )
+            .await
+    }
+
+    pub async fn update_isolated_margin(
+        &self,
+        amount: f64,
+        coin: &str,
+        options: Option<ActionOptions>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.exchange_client
+            .update_isolated_margin_for_vault(amount, coin, self.vault(options)?, options, wallet)
+            .await
+    }
+}
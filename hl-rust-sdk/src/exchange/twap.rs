@@ -0,0 +1,43 @@
+use crate::{errors::Error, helpers::float_to_string_for_hashing, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub struct ClientTwapRequest {
+    pub asset: String,
+    pub is_buy: bool,
+    pub reduce_only: bool,
+    pub sz: f64,
+    pub minutes: u32,
+    pub randomize: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TwapRequest {
+    #[serde(rename = "a", alias = "asset")]
+    pub asset: u32,
+    #[serde(rename = "b", alias = "isBuy")]
+    pub is_buy: bool,
+    #[serde(rename = "s", alias = "sz")]
+    pub sz: String,
+    #[serde(rename = "r", alias = "reduceOnly")]
+    pub reduce_only: bool,
+    #[serde(rename = "m", alias = "minutes")]
+    pub minutes: u32,
+    #[serde(rename = "t", alias = "randomize")]
+    pub randomize: bool,
+}
+
+impl ClientTwapRequest {
+    pub(crate) fn convert(self, coin_to_asset: &HashMap<String, u32>) -> Result<TwapRequest> {
+        let &asset = coin_to_asset.get(&self.asset).ok_or(Error::AssetNotFound)?;
+
+        Ok(TwapRequest {
+            asset,
+            is_buy: self.is_buy,
+            sz: float_to_string_for_hashing(self.sz),
+            reduce_only: self.reduce_only,
+            minutes: self.minutes,
+            randomize: self.randomize,
+        })
+    }
+}
@@ -1,29 +1,13 @@
 use crate::{consts::*, prelude::*, Error};
 use chrono::prelude::Utc;
-use lazy_static::lazy_static;
-use log::info;
 use rand::{thread_rng, Rng};
-use std::sync::atomic::{AtomicU64, Ordering};
 use uuid::Uuid;
 
-fn now_timestamp_ms() -> u64 {
+pub(crate) fn now_timestamp_ms() -> u64 {
     let now = Utc::now();
     now.timestamp_millis() as u64
 }
 
-pub(crate) fn next_nonce() -> u64 {
-    let nonce = CUR_NONCE.fetch_add(1, Ordering::Relaxed);
-    let now_ms = now_timestamp_ms();
-    if nonce > now_ms + 1000 {
-        info!("nonce progressed too far ahead {nonce} {now_ms}");
-    }
-    // more than 300 seconds behind
-    if nonce + 300000 < now_ms {
-        CUR_NONCE.fetch_max(now_ms, Ordering::Relaxed);
-    }
-    nonce
-}
-
 pub(crate) const WIRE_DECIMALS: u8 = 8;
 
 pub(crate) fn float_to_string_for_hashing(x: f64) -> String {
@@ -59,6 +43,11 @@ pub(crate) fn generate_random_key() -> Result<[u8; 32]> {
     Ok(arr)
 }
 
+pub(crate) fn round_to_decimals(x: f64, decimals: u32) -> f64 {
+    let pow10 = 10f64.powi(decimals as i32);
+    (x * pow10).round() / pow10
+}
+
 pub fn truncate_float(float: f64, decimals: u32, round_up: bool) -> f64 {
     let pow10 = 10i64.pow(decimals) as f64;
     let mut float = (float * pow10) as u64;
@@ -76,11 +65,18 @@ pub fn bps_diff(x: f64, y: f64) -> u16 {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum BaseUrl {
     Localhost,
     Testnet,
     Mainnet,
+    /// Any other endpoint, e.g. a local node or a proxy. `is_mainnet` picks the network that
+    /// actions are signed for.
+    Custom {
+        http: String,
+        ws: String,
+        is_mainnet: bool,
+    },
 }
 
 impl BaseUrl {
@@ -89,12 +85,33 @@ impl BaseUrl {
             BaseUrl::Localhost => LOCAL_API_URL.to_string(),
             BaseUrl::Mainnet => MAINNET_API_URL.to_string(),
             BaseUrl::Testnet => TESTNET_API_URL.to_string(),
+            BaseUrl::Custom { http, .. } => http.clone(),
+        }
+    }
+
+    pub(crate) fn get_ws_url(&self) -> String {
+        match self {
+            BaseUrl::Custom { ws, .. } => ws.clone(),
+            _ => format!("ws{}/ws", &self.get_url()[4..]),
         }
     }
-}
 
-lazy_static! {
-    static ref CUR_NONCE: AtomicU64 = AtomicU64::new(now_timestamp_ms());
+    pub fn is_mainnet(&self) -> bool {
+        match self {
+            BaseUrl::Mainnet => true,
+            BaseUrl::Localhost | BaseUrl::Testnet => false,
+            BaseUrl::Custom { is_mainnet, .. } => *is_mainnet,
+        }
+    }
+
+    /// Network named in user signed actions, "Mainnet" or "Testnet"
+    pub fn hyperliquid_chain(&self) -> &'static str {
+        if self.is_mainnet() {
+            "Mainnet"
+        } else {
+            "Testnet"
+        }
+    }
 }
 
 #[cfg(test)]
@@ -141,4 +158,25 @@ mod tests {
             "987654321".to_string()
         );
     }
+
+    #[test]
+    fn base_url_test() {
+        assert_eq!(BaseUrl::Localhost.get_ws_url(), "ws://localhost:3001/ws");
+        assert_eq!(
+            BaseUrl::Mainnet.get_ws_url(),
+            "wss://api.hyperliquid.xyz/ws"
+        );
+        assert!(BaseUrl::Mainnet.is_mainnet() && !BaseUrl::Testnet.is_mainnet());
+        assert_eq!(BaseUrl::Mainnet.hyperliquid_chain(), "Mainnet");
+        assert_eq!(BaseUrl::Localhost.hyperliquid_chain(), "Testnet");
+
+        let base_url = BaseUrl::Custom {
+            http: "http://127.0.0.1:8080/hl".to_string(),
+            ws: "ws://127.0.0.1:8081".to_string(),
+            is_mainnet: true,
+        };
+        assert_eq!(base_url.get_url(), "http://127.0.0.1:8080/hl");
+        assert_eq!(base_url.get_ws_url(), "ws://127.0.0.1:8081");
+        assert!(base_url.is_mainnet());
+    }
 }
@@ -0,0 +1,305 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    meta::{Meta, SpotMeta},
+    prelude::*,
+    Error, OrderNormalizer,
+};
+
+/// Asset ids, names and decimals from one fetch of `meta` and `spotMeta`
+#[derive(Debug, Clone)]
+pub struct AssetSnapshot {
+    pub meta: Meta,
+    pub spot_meta: SpotMeta,
+    /// Perp names, spot pair names ("PURR/USDC") and spot names ("@1") to asset ids
+    pub coin_to_asset: HashMap<String, u32>,
+    /// Asset ids to perp names and spot pair names
+    pub asset_to_coin: HashMap<u32, String>,
+    /// Spot names as used by the exchange ("@1") to pair names ("HFUN/USDC")
+    pub spot_pair_names: HashMap<String, String>,
+    pub normalizer: OrderNormalizer,
+}
+
+impl AssetSnapshot {
+    pub fn new(meta: Meta, spot_meta: SpotMeta) -> AssetSnapshot {
+        let mut coin_to_asset = HashMap::new();
+        for (asset_ind, asset) in meta.universe.iter().enumerate() {
+            coin_to_asset.insert(asset.name.clone(), asset_ind as u32);
+        }
+        let coin_to_asset = spot_meta.add_pair_and_name_to_index_map(coin_to_asset);
+
+        let index_to_name: HashMap<usize, &str> = spot_meta
+            .tokens
+            .iter()
+            .map(|info| (info.index, info.name.as_str()))
+            .collect();
+        let mut spot_pair_names = HashMap::new();
+        for asset in spot_meta.universe.iter() {
+            if let (Some(base), Some(quote)) = (
+                index_to_name.get(&asset.tokens[0]),
+                index_to_name.get(&asset.tokens[1]),
+            ) {
+                spot_pair_names.insert(asset.name.clone(), format!("{base}/{quote}"));
+            }
+        }
+
+        let mut asset_to_coin: HashMap<u32, String> = meta
+            .universe
+            .iter()
+            .enumerate()
+            .map(|(asset_ind, asset)| (asset_ind as u32, asset.name.clone()))
+            .collect();
+        for asset in spot_meta.universe.iter() {
+            let name = spot_pair_names.get(&asset.name).unwrap_or(&asset.name);
+            asset_to_coin.insert(10000 + asset.index as u32, name.clone());
+        }
+
+        let normalizer = OrderNormalizer::new(&meta, &spot_meta);
+        AssetSnapshot {
+            meta,
+            spot_meta,
+            coin_to_asset,
+            asset_to_coin,
+            spot_pair_names,
+            normalizer,
+        }
+    }
+
+    pub fn asset(&self, coin: &str) -> Result<u32> {
+        self.coin_to_asset
+            .get(coin)
+            .copied()
+            .ok_or(Error::AssetNotFound)
+    }
+
+    pub fn coin(&self, asset: u32) -> Option<&str> {
+        self.asset_to_coin.get(&asset).map(String::as_str)
+    }
+
+    /// Pair name of a spot name like "@1", or the name itself for perps and canonical pairs
+    pub fn display_name<'a>(&'a self, coin: &'a str) -> &'a str {
+        self.spot_pair_names.get(coin).map_or(coin, String::as_str)
+    }
+
+    pub fn sz_decimals(&self, coin: &str) -> Result<u32> {
+        self.normalizer.sz_decimals(coin)
+    }
+
+    fn contains_all<'a>(&self, mut coins: impl Iterator<Item = &'a str>) -> bool {
+        coins.all(|coin| self.coin_to_asset.contains_key(coin))
+    }
+}
+
+impl Default for AssetSnapshot {
+    fn default() -> AssetSnapshot {
+        AssetSnapshot::new(
+            Meta {
+                universe: Vec::new(),
+            },
+            SpotMeta {
+                universe: Vec::new(),
+                tokens: Vec::new(),
+            },
+        )
+    }
+}
+
+/// Latest `AssetSnapshot`, shared by an `InfoClient` and the `ExchangeClient` built on it.
+/// The `InfoClient` fetches a new snapshot when the current one is older than
+/// `refresh_interval`, or older than `min_refresh_interval` and doesn't know a coin that is
+/// looked up, so newly listed assets can be traded without restarting. Clients built
+/// separately share one through `with_asset_registry`, and `InfoClient::spawn_asset_refresh`
+/// refreshes it on a schedule as well.
+#[derive(Debug)]
+pub struct AssetRegistry {
+    pub refresh_interval: Duration,
+    pub min_refresh_interval: Duration,
+    snapshot: RwLock<Arc<AssetSnapshot>>,
+    refreshed_at: Mutex<Option<Instant>>,
+}
+
+impl Default for AssetRegistry {
+    fn default() -> AssetRegistry {
+        AssetRegistry {
+            refresh_interval: Duration::from_secs(300),
+            min_refresh_interval: Duration::from_secs(5),
+            snapshot: RwLock::new(Arc::new(AssetSnapshot::default())),
+            refreshed_at: Mutex::new(None),
+        }
+    }
+}
+
+impl AssetRegistry {
+    /// Empty registry, filled on first use
+    pub fn new(refresh_interval: Duration) -> AssetRegistry {
+        AssetRegistry {
+            refresh_interval,
+            ..AssetRegistry::default()
+        }
+    }
+
+    pub fn snapshot(&self) -> Arc<AssetSnapshot> {
+        self.snapshot
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn update(&self, snapshot: AssetSnapshot) -> Arc<AssetSnapshot> {
+        let snapshot = Arc::new(snapshot);
+        *self.snapshot.write().unwrap_or_else(|e| e.into_inner()) = snapshot.clone();
+        *self.refreshed_at.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now());
+        snapshot
+    }
+
+    fn age(&self) -> Option<Duration> {
+        self.refreshed_at
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .map(|refreshed_at| refreshed_at.elapsed())
+    }
+
+    pub fn is_stale(&self) -> bool {
+        self.age().is_none_or(|age| age >= self.refresh_interval)
+    }
+
+    /// Current snapshot unless it should be refreshed before looking up `coins`
+    pub(crate) fn fresh_snapshot<'a>(
+        &self,
+        coins: impl Iterator<Item = &'a str>,
+    ) -> Option<Arc<AssetSnapshot>> {
+        let age = self.age()?;
+        let snapshot = self.snapshot();
+        let refresh = age >= self.refresh_interval
+            || (age >= self.min_refresh_interval && !snapshot.contains_all(coins));
+        (!refresh).then_some(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AssetMeta, InfoClient, MockTransport, SpotAssetMeta, TokenInfo};
+    use ethers::types::H128;
+    use serde_json::json;
+
+    fn get_snapshot() -> AssetSnapshot {
+        let meta = Meta {
+            universe: vec![
+                AssetMeta {
+                    name: "BTC".to_string(),
+                    sz_decimals: 5,
+                },
+                AssetMeta {
+                    name: "ETH".to_string(),
+                    sz_decimals: 4,
+                },
+            ],
+        };
+        let token = |name: &str, index| TokenInfo {
+            name: name.to_string(),
+            sz_decimals: 0,
+            wei_decimals: 8,
+            index,
+            token_id: H128::zero(),
+            is_canonical: true,
+        };
+        let spot_meta = SpotMeta {
+            universe: vec![SpotAssetMeta {
+                tokens: [1, 0],
+                name: "@1".to_string(),
+                index: 1,
+                is_canonical: false,
+            }],
+            tokens: vec![token("USDC", 0), token("HFUN", 1)],
+        };
+        AssetSnapshot::new(meta, spot_meta)
+    }
+
+    #[test]
+    fn test_asset_snapshot_lookups() -> Result<()> {
+        let snapshot = get_snapshot();
+        assert_eq!(snapshot.asset("ETH")?, 1);
+        assert_eq!(snapshot.asset("@1")?, 10001);
+        assert_eq!(snapshot.asset("HFUN/USDC")?, 10001);
+        assert!(snapshot.asset("DOGE").is_err());
+
+        assert_eq!(snapshot.coin(0), Some("BTC"));
+        assert_eq!(snapshot.coin(10001), Some("HFUN/USDC"));
+        assert_eq!(snapshot.display_name("@1"), "HFUN/USDC");
+        assert_eq!(snapshot.display_name("ETH"), "ETH");
+        assert_eq!(snapshot.sz_decimals("BTC")?, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_asset_registry_staleness() {
+        let mut registry = AssetRegistry::new(Duration::from_secs(60));
+        assert!(registry.is_stale());
+        assert!(registry.fresh_snapshot(["ETH"].into_iter()).is_none());
+
+        registry.update(get_snapshot());
+        assert!(registry.fresh_snapshot(["ETH", "@1"].into_iter()).is_some());
+        // Unknown coins don't trigger refreshes more often than the minimum interval
+        assert!(registry.fresh_snapshot(["DOGE"].into_iter()).is_some());
+        registry.min_refresh_interval = Duration::ZERO;
+        assert!(registry.fresh_snapshot(["DOGE"].into_iter()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_info_client_refreshes_on_lookup_miss() -> Result<()> {
+        // Lists a new coin with every `meta` request
+        let listed = Arc::new(Mutex::new(vec![json!({"name": "BTC", "szDecimals": 5})]));
+        let transport = MockTransport::new(move |_, body| match body["type"].as_str() {
+            Some("meta") => {
+                let mut listed = listed.lock().unwrap_or_else(|e| e.into_inner());
+                let universe = json!(*listed);
+                let name = format!("NEW{}", listed.len());
+                listed.push(json!({"name": name, "szDecimals": 0}));
+                Ok(json!({ "universe": universe }))
+            }
+            _ => Ok(json!({"universe": [], "tokens": []})),
+        });
+        let mut info_client = InfoClient::with_transport(Arc::new(transport), None).await?;
+        info_client.asset_registry = Arc::new(AssetRegistry {
+            min_refresh_interval: Duration::ZERO,
+            ..AssetRegistry::default()
+        });
+
+        assert!(info_client.assets().await?.asset("NEW1").is_err());
+        assert_eq!(info_client.assets_for(["NEW1"]).await?.asset("NEW1")?, 1);
+        assert_eq!(info_client.assets().await?.coin(1), Some("NEW1"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shared_asset_registry_refreshes_periodically() -> Result<()> {
+        let meta_requests = Arc::new(Mutex::new(0));
+        let requests = meta_requests.clone();
+        let transport = MockTransport::new(move |_, body| match body["type"].as_str() {
+            Some("meta") => {
+                *requests.lock().unwrap_or_else(|e| e.into_inner()) += 1;
+                Ok(json!({"universe": [{"name": "BTC", "szDecimals": 5}]}))
+            }
+            _ => Ok(json!({"universe": [], "tokens": []})),
+        });
+        let registry = Arc::new(AssetRegistry::new(Duration::from_secs(3600)));
+        let info_client = Arc::new(
+            InfoClient::with_transport(Arc::new(transport), None)
+                .await?
+                .with_asset_registry(registry.clone()),
+        );
+
+        let refresh = InfoClient::spawn_asset_refresh(&info_client, Duration::from_millis(10));
+        tokio::time::sleep(Duration::from_millis(55)).await;
+        refresh.abort();
+
+        assert!(*meta_requests.lock().unwrap_or_else(|e| e.into_inner()) >= 2);
+        assert_eq!(registry.snapshot().asset("BTC")?, 0);
+        Ok(())
+    }
+}
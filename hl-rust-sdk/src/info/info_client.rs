@@ -1,21 +1,28 @@
 use crate::{
     info::{
-        CandlesSnapshotResponse, FundingHistoryResponse, L2SnapshotResponse, OpenOrdersResponse,
-        RecentTradesResponse, UserFillsResponse, UserStateResponse,
+        AssetRegistry, AssetSnapshot, CandlesSnapshotResponse, FundingHistoryResponse,
+        L2SnapshotResponse, OpenOrdersResponse, RecentTradesResponse, UserFillsResponse,
+        UserStateResponse,
     },
     meta::{Meta, SpotMeta, SpotMetaAndAssetCtxs},
     prelude::*,
     req::HttpClient,
+    transport::{ReqwestTransport, Transport},
     ws::{Subscription, WsManager},
-    BaseUrl, Error, Message, OrderStatusResponse, ReferralResponse, UserFeesResponse,
-    UserFundingResponse, UserTokenBalanceResponse,
+    BaseUrl, Error, Message, OrderStatusResponse, ReferralResponse, SubAccount, TwapSliceFill,
+    UserFeesResponse, UserFundingResponse, UserTokenBalanceResponse,
 };
 
 use ethers::types::H160;
+use log::error;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use tokio::sync::mpsc::UnboundedSender;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Weak},
+    time::Duration,
+};
+use tokio::{spawn, sync::mpsc::UnboundedSender, task::JoinHandle, time};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -84,32 +91,102 @@ pub enum InfoRequest {
     Referral {
         user: H160,
     },
+    SubAccounts {
+        user: H160,
+    },
+    UserTwapSliceFills {
+        user: H160,
+    },
+    MaxBuilderFee {
+        user: H160,
+        builder: H160,
+    },
+}
+
+impl InfoRequest {
+    /// Request weight counted by the exchange towards the rate limit
+    pub fn weight(&self) -> u32 {
+        match self {
+            InfoRequest::L2Book { .. }
+            | InfoRequest::AllMids
+            | InfoRequest::UserState { .. }
+            | InfoRequest::UserTokenBalances { .. }
+            | InfoRequest::OrderStatus { .. } => 2,
+            _ => 20,
+        }
+    }
 }
 
 pub struct InfoClient {
     pub http_client: HttpClient,
+    pub asset_registry: Arc<AssetRegistry>,
     pub(crate) ws_manager: Option<WsManager>,
 }
 
 impl InfoClient {
     pub async fn new(client: Option<Client>, base_url: Option<BaseUrl>) -> Result<InfoClient> {
         let client = client.unwrap_or_default();
-        let base_url = base_url.unwrap_or(BaseUrl::Mainnet).get_url();
+        let base_url = base_url.unwrap_or(BaseUrl::Mainnet);
+        let transport = Arc::new(ReqwestTransport::new(client, base_url.get_url()));
+        InfoClient::with_transport(transport, Some(base_url)).await
+    }
+
+    /// Sends requests through `transport` rather than over HTTP, e.g. to a `MockTransport` in
+    /// tests. Subscriptions connect to the websocket of `base_url` unless the transport serves
+    /// them itself.
+    pub async fn with_transport(
+        transport: Arc<dyn Transport>,
+        base_url: Option<BaseUrl>,
+    ) -> Result<InfoClient> {
+        let base_url = base_url.unwrap_or(BaseUrl::Mainnet);
 
         Ok(InfoClient {
-            http_client: HttpClient { client, base_url },
+            http_client: HttpClient::new(transport, base_url),
+            asset_registry: Arc::new(AssetRegistry::default()),
             ws_manager: None,
         })
     }
 
+    /// Looks assets up in `asset_registry` rather than in a registry of its own, so clients
+    /// built separately, e.g. an `InfoClient` and an `ExchangeClient`, refresh one snapshot
+    pub fn with_asset_registry(mut self, asset_registry: Arc<AssetRegistry>) -> InfoClient {
+        self.asset_registry = asset_registry;
+        self
+    }
+
+    /// Every `interval`, refreshes the asset registry of `client` on top of the refreshes
+    /// triggered by lookups. The task stops once the client is dropped or the handle is aborted.
+    pub fn spawn_asset_refresh(client: &Arc<InfoClient>, interval: Duration) -> JoinHandle<()> {
+        let client: Weak<InfoClient> = Arc::downgrade(client);
+        spawn(async move {
+            let mut ticker = time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let Some(client) = client.upgrade() else {
+                    break;
+                };
+                if let Err(e) = client.refresh_assets().await {
+                    error!("Error refreshing assets: {e}");
+                }
+            }
+        })
+    }
+
     pub async fn subscribe(
         &mut self,
         subscription: Subscription,
         sender_channel: UnboundedSender<Message>,
     ) -> Result<u32> {
+        if let Some(subscription_id) = self
+            .http_client
+            .transport
+            .subscribe(&subscription, sender_channel.clone())
+        {
+            return subscription_id;
+        }
+
         if self.ws_manager.is_none() {
-            let ws_manager =
-                WsManager::new(format!("ws{}/ws", &self.http_client.base_url[4..])).await?;
+            let ws_manager = WsManager::new(self.http_client.base_url.get_ws_url()).await?;
             self.ws_manager = Some(ws_manager);
         }
 
@@ -124,9 +201,12 @@ impl InfoClient {
     }
 
     pub async fn unsubscribe(&mut self, subscription_id: u32) -> Result<()> {
+        if let Some(res) = self.http_client.transport.unsubscribe(subscription_id) {
+            return res;
+        }
+
         if self.ws_manager.is_none() {
-            let ws_manager =
-                WsManager::new(format!("ws{}/ws", &self.http_client.base_url[4..])).await?;
+            let ws_manager = WsManager::new(self.http_client.base_url.get_ws_url()).await?;
             self.ws_manager = Some(ws_manager);
         }
 
@@ -144,10 +224,38 @@ impl InfoClient {
         let data =
             serde_json::to_string(&info_request).map_err(|e| Error::JsonParse(e.to_string()))?;
 
-        let return_data = self.http_client.post("/info", data).await?;
+        let return_data = self
+            .http_client
+            .post("/info", data, info_request.weight(), true)
+            .await?;
         serde_json::from_str(&return_data).map_err(|e| Error::JsonParse(e.to_string()))
     }
 
+    /// Fetches `meta` and `spotMeta` and replaces the snapshot of the asset registry
+    pub async fn refresh_assets(&self) -> Result<Arc<AssetSnapshot>> {
+        let meta = self.meta().await?;
+        let spot_meta = self.spot_meta().await?;
+        Ok(self
+            .asset_registry
+            .update(AssetSnapshot::new(meta, spot_meta)))
+    }
+
+    /// Asset snapshot to look up `coins` in, refreshed first if it's stale or doesn't know
+    /// one of them
+    pub async fn assets_for<'a>(
+        &self,
+        coins: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Arc<AssetSnapshot>> {
+        match self.asset_registry.fresh_snapshot(coins.into_iter()) {
+            Some(snapshot) => Ok(snapshot),
+            None => self.refresh_assets().await,
+        }
+    }
+
+    pub async fn assets(&self) -> Result<Arc<AssetSnapshot>> {
+        self.assets_for([]).await
+    }
+
     pub async fn open_orders(&self, address: H160) -> Result<Vec<OpenOrdersResponse>> {
         let input = InfoRequest::OpenOrders { user: address };
         self.send_info_request(input).await
@@ -263,4 +371,22 @@ impl InfoClient {
         let input = InfoRequest::Referral { user: address };
         self.send_info_request(input).await
     }
+
+    pub async fn sub_accounts(&self, address: H160) -> Result<Vec<SubAccount>> {
+        let input = InfoRequest::SubAccounts { user: address };
+        // Users without sub-accounts get `null` back
+        let sub_accounts: Option<Vec<SubAccount>> = self.send_info_request(input).await?;
+        Ok(sub_accounts.unwrap_or_default())
+    }
+
+    pub async fn user_twap_slice_fills(&self, address: H160) -> Result<Vec<TwapSliceFill>> {
+        let input = InfoRequest::UserTwapSliceFills { user: address };
+        self.send_info_request(input).await
+    }
+
+    /// Maximum fee, in tenths of a basis point, that `user` has approved for `builder`
+    pub async fn max_builder_fee(&self, user: H160, builder: H160) -> Result<u64> {
+        let input = InfoRequest::MaxBuilderFee { user, builder };
+        self.send_info_request(input).await
+    }
 }
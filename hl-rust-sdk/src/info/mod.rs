@@ -1,6 +1,8 @@
+mod asset_registry;
 pub(super) mod info_client;
 mod response_structs;
 mod sub_structs;
 
+pub use asset_registry::{AssetRegistry, AssetSnapshot};
 pub use response_structs::*;
 pub use sub_structs::*;
@@ -2,6 +2,7 @@ use crate::{
     info::{AssetPosition, Level, MarginSummary},
     DailyUserVlm, Delta, FeeSchedule, OrderInfo, Referrer, ReferrerState, UserTokenBalance,
 };
+use ethers::types::H160;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
@@ -39,6 +40,12 @@ pub struct OpenOrdersResponse {
     pub timestamp: u64,
 }
 
+#[cfg(feature = "rust_decimal")]
+crate::number::decimal_accessors!(OpenOrdersResponse {
+    limit_px_decimal: limit_px,
+    sz_decimal: sz,
+});
+
 #[derive(serde::Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct UserFillsResponse {
@@ -55,6 +62,14 @@ pub struct UserFillsResponse {
     pub time: u64,
 }
 
+#[cfg(feature = "rust_decimal")]
+crate::number::decimal_accessors!(UserFillsResponse {
+    closed_pnl_decimal: closed_pnl,
+    px_decimal: px,
+    start_position_decimal: start_position,
+    sz_decimal: sz,
+});
+
 #[derive(serde::Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct FundingHistoryResponse {
@@ -129,3 +144,13 @@ pub struct ReferralResponse {
     pub claimed_rewards: String,
     pub referrer_state: ReferrerState,
 }
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccount {
+    pub name: String,
+    pub sub_account_user: H160,
+    pub master: H160,
+    pub clearinghouse_state: UserStateResponse,
+    pub spot_state: UserTokenBalanceResponse,
+}
@@ -24,6 +24,17 @@ pub struct PositionData {
     pub unrealized_pnl: String,
 }
 
+#[cfg(feature = "rust_decimal")]
+crate::number::decimal_accessors!(PositionData {
+    entry_px_decimal: entry_px?,
+    liquidation_px_decimal: liquidation_px?,
+    margin_used_decimal: margin_used,
+    position_value_decimal: position_value,
+    return_on_equity_decimal: return_on_equity,
+    szi_decimal: szi,
+    unrealized_pnl_decimal: unrealized_pnl,
+});
+
 #[derive(Deserialize, Debug)]
 pub struct AssetPosition {
     pub position: PositionData,
@@ -48,6 +59,12 @@ pub struct Level {
     pub sz: String,
 }
 
+#[cfg(feature = "rust_decimal")]
+crate::number::decimal_accessors!(Level {
+    px_decimal: px,
+    sz_decimal: sz,
+});
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Delta {
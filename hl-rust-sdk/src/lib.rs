@@ -6,16 +6,28 @@ mod helpers;
 mod info;
 mod market_maker;
 mod meta;
+mod nonce;
+mod number;
+mod paper;
 mod prelude;
 mod proxy_digest;
 mod req;
 mod signature;
+mod transport;
 mod ws;
-pub use consts::{EPSILON, LOCAL_API_URL, MAINNET_API_URL, TESTNET_API_URL};
+pub use consts::{
+    ARBITRUM_ONE_CHAIN_ID, ARBITRUM_SEPOLIA_CHAIN_ID, EPSILON, LOCAL_API_URL, MAINNET_API_URL,
+    TESTNET_API_URL,
+};
 pub use errors::Error;
 pub use exchange::*;
 pub use helpers::{bps_diff, truncate_float, BaseUrl};
 pub use info::{info_client::*, *};
 pub use market_maker::{MarketMaker, MarketMakerInput, MarketMakerRestingOrder};
 pub use meta::*;
+pub use nonce::{FileNonceManager, InMemoryNonceManager, NonceManager};
+pub use paper::PaperExchange;
+pub use req::{RetryPolicy, WeightBudget};
+pub use signature::{recover_signer, signing_hash, verify_signer, HyperliquidSigner};
+pub use transport::{MockTransport, ReqwestTransport, Transport};
 pub use ws::*;
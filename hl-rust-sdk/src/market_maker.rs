@@ -3,13 +3,14 @@ use ethers::{
     types::H160,
 };
 use log::{error, info};
+use std::sync::Arc;
 
 use tokio::sync::mpsc::unbounded_channel;
 
 use crate::{
     bps_diff, truncate_float, BaseUrl, ClientCancelRequest, ClientLimit, ClientOrder,
-    ClientOrderRequest, ExchangeClient, ExchangeDataStatus, ExchangeResponseStatus, InfoClient,
-    Message, Subscription, UserData, EPSILON,
+    ClientOrderRequest, ExchangeClient, ExchangeDataStatus, ExchangeErrorKind,
+    ExchangeResponseStatus, InfoClient, Message, Subscription, Tif, Transport, UserData, EPSILON,
 };
 #[derive(Debug)]
 pub struct MarketMakerRestingOrder {
@@ -46,13 +47,50 @@ pub struct MarketMaker {
 
 impl MarketMaker {
     pub async fn new(input: MarketMakerInput) -> MarketMaker {
-        let user_address = input.wallet.address();
-
         let info_client = InfoClient::new(None, Some(BaseUrl::Testnet)).await.unwrap();
-        let exchange_client =
-            ExchangeClient::new(None, input.wallet, Some(BaseUrl::Testnet), None, None)
-                .await
-                .unwrap();
+        let exchange_client = ExchangeClient::new(
+            None,
+            input.wallet.clone(),
+            Some(BaseUrl::Testnet),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        MarketMaker::with_clients(input, info_client, exchange_client)
+    }
+
+    /// Market makes through `transport`, e.g. a `PaperExchange`, with clients signing for
+    /// testnet. Subscriptions go to the transport too if it serves them.
+    pub async fn with_transport(
+        input: MarketMakerInput,
+        transport: Arc<dyn Transport>,
+    ) -> MarketMaker {
+        let info_client = InfoClient::with_transport(transport.clone(), Some(BaseUrl::Testnet))
+            .await
+            .unwrap();
+        let exchange_client = ExchangeClient::with_transport(
+            transport,
+            input.wallet.clone(),
+            Some(BaseUrl::Testnet),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        MarketMaker::with_clients(input, info_client, exchange_client)
+    }
+
+    fn with_clients(
+        input: MarketMakerInput,
+        info_client: InfoClient,
+        exchange_client: ExchangeClient,
+    ) -> MarketMaker {
+        let user_address = input.wallet.address();
 
         MarketMaker {
             asset: input.asset,
@@ -151,7 +189,7 @@ impl MarketMaker {
     async fn attempt_cancel(&self, asset: String, oid: u64) -> bool {
         let cancel = self
             .exchange_client
-            .cancel(ClientCancelRequest { asset, oid }, None)
+            .cancel(ClientCancelRequest { asset, oid }, None, None)
             .await;
 
         match cancel {
@@ -163,6 +201,11 @@ impl MarketMaker {
                                 ExchangeDataStatus::Success => {
                                     return true;
                                 }
+                                ExchangeDataStatus::Error(e)
+                                    if e.kind == ExchangeErrorKind::OrderNotFound =>
+                                {
+                                    info!("Order {oid} was filled before it could be cancelled")
+                                }
                                 ExchangeDataStatus::Error(e) => {
                                     error!("Error with cancelling: {e}")
                                 }
@@ -199,11 +242,10 @@ impl MarketMaker {
                     limit_px: price,
                     sz: amount,
                     cloid: None,
-                    order_type: ClientOrder::Limit(ClientLimit {
-                        tif: "Gtc".to_string(),
-                    }),
+                    order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
                 },
                 None,
+                None,
             )
             .await;
         match order {
@@ -0,0 +1,154 @@
+use ethers::types::H160;
+use lazy_static::lazy_static;
+use log::info;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use crate::{helpers::now_timestamp_ms, prelude::*, Error};
+
+/// Hands out the nonces attached to signed actions. The exchange only accepts nonces within a
+/// window around the current time and never the same nonce twice for a signer, so sequences
+/// are kept per signing address.
+pub trait NonceManager: Send + Sync {
+    fn next_nonce(&self, address: H160) -> Result<u64>;
+}
+
+/// Next nonce for `address`: the current time in milliseconds, or one past the last nonce
+/// when several actions are signed within the same millisecond
+fn advance(nonces: &mut HashMap<H160, u64>, address: H160) -> u64 {
+    let now_ms = now_timestamp_ms();
+    let nonce = nonces
+        .get(&address)
+        .map_or(now_ms, |last| now_ms.max(last + 1));
+    if nonce > now_ms + 1000 {
+        info!("nonce progressed too far ahead {nonce} {now_ms}");
+    }
+    nonces.insert(address, nonce);
+    nonce
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryNonceManager {
+    nonces: Mutex<HashMap<H160, u64>>,
+}
+
+impl InMemoryNonceManager {
+    pub fn new() -> InMemoryNonceManager {
+        InMemoryNonceManager::default()
+    }
+}
+
+impl NonceManager for InMemoryNonceManager {
+    fn next_nonce(&self, address: H160) -> Result<u64> {
+        let mut nonces = self
+            .nonces
+            .lock()
+            .map_err(|e| Error::NonceStore(e.to_string()))?;
+        Ok(advance(&mut nonces, address))
+    }
+}
+
+/// Keeps the last nonce of every address in a JSON file so that sequences survive restarts.
+/// The file is rewritten each time a nonce is handed out.
+#[derive(Debug)]
+pub struct FileNonceManager {
+    path: PathBuf,
+    nonces: Mutex<HashMap<H160, u64>>,
+}
+
+impl FileNonceManager {
+    /// Loads the nonces stored at `path`, starting empty if the file doesn't exist yet
+    pub fn new(path: impl AsRef<Path>) -> Result<FileNonceManager> {
+        let path = path.as_ref().to_path_buf();
+        let nonces = match fs::read_to_string(&path) {
+            Ok(contents) => {
+                serde_json::from_str(&contents).map_err(|e| Error::JsonParse(e.to_string()))?
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(Error::NonceStore(e.to_string())),
+        };
+
+        Ok(FileNonceManager {
+            path,
+            nonces: Mutex::new(nonces),
+        })
+    }
+
+    fn persist(&self, nonces: &HashMap<H160, u64>) -> Result<()> {
+        let contents =
+            serde_json::to_string(nonces).map_err(|e| Error::JsonParse(e.to_string()))?;
+        // Write to a temporary file first so a crash can't leave a truncated store behind
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, contents).map_err(|e| Error::NonceStore(e.to_string()))?;
+        fs::rename(&tmp_path, &self.path).map_err(|e| Error::NonceStore(e.to_string()))
+    }
+}
+
+impl NonceManager for FileNonceManager {
+    fn next_nonce(&self, address: H160) -> Result<u64> {
+        let mut nonces = self
+            .nonces
+            .lock()
+            .map_err(|e| Error::NonceStore(e.to_string()))?;
+        let nonce = advance(&mut nonces, address);
+        self.persist(&nonces)?;
+        Ok(nonce)
+    }
+}
+
+lazy_static! {
+    static ref SHARED_NONCE_MANAGER: Arc<InMemoryNonceManager> =
+        Arc::new(InMemoryNonceManager::new());
+}
+
+/// Process-wide in-memory manager used when none is given, so that clients created for the
+/// same wallet don't hand out the same nonce
+pub(crate) fn shared_nonce_manager() -> Arc<dyn NonceManager> {
+    SHARED_NONCE_MANAGER.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nonces_are_per_address_and_increasing() -> Result<()> {
+        let nonce_manager = InMemoryNonceManager::new();
+        let first = H160::from_low_u64_be(1);
+        let second = H160::from_low_u64_be(2);
+
+        let a = nonce_manager.next_nonce(first)?;
+        let b = nonce_manager.next_nonce(first)?;
+        let c = nonce_manager.next_nonce(second)?;
+        assert!(b > a);
+        // A fresh address starts from the current time rather than after another's sequence
+        assert!(c + 1000 > now_timestamp_ms());
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_nonce_manager_survives_restart() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("nonces-{}.json", uuid::Uuid::new_v4()));
+        let address = H160::from_low_u64_be(1);
+
+        let far_ahead = now_timestamp_ms() + 60_000;
+        fs::write(
+            &path,
+            serde_json::to_string(&HashMap::from([(address, far_ahead)]))
+                .map_err(|e| Error::JsonParse(e.to_string()))?,
+        )
+        .map_err(|e| Error::NonceStore(e.to_string()))?;
+
+        let nonce = FileNonceManager::new(&path)?.next_nonce(address)?;
+        assert_eq!(nonce, far_ahead + 1);
+        let nonce = FileNonceManager::new(&path)?.next_nonce(address)?;
+        assert_eq!(nonce, far_ahead + 2);
+
+        fs::remove_file(&path).map_err(|e| Error::NonceStore(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+//! Prices, sizes and amounts. The exchange sends them as decimal strings, which response structs
+//! keep as is. With the `rust_decimal` feature they also get `*_decimal()` accessors parsing those
+//! strings into exact `Decimal`s.
+
+use crate::{prelude::*, Error};
+
+pub(crate) fn parse_number(s: &str) -> Result<f64> {
+    s.parse().map_err(|_| Error::FloatStringParse)
+}
+
+#[cfg(feature = "rust_decimal")]
+pub(crate) fn parse_decimal(s: &str) -> Result<rust_decimal::Decimal> {
+    s.parse().map_err(|_| Error::FloatStringParse)
+}
+
+/// Decimal counterpart of `float_to_string_for_hashing`
+#[cfg(feature = "rust_decimal")]
+pub(crate) fn decimal_to_wire(x: rust_decimal::Decimal) -> String {
+    let x = x.round_dp(crate::helpers::WIRE_DECIMALS.into()).normalize();
+    if x.is_zero() {
+        "0".to_string()
+    } else {
+        x.to_string()
+    }
+}
+
+/// Implements `<accessor>(&self) -> Result<Decimal>` for each `accessor: field,` of a struct, and
+/// `Result<Option<Decimal>>` for optional fields listed as `accessor: field?,`
+#[cfg(feature = "rust_decimal")]
+macro_rules! decimal_accessors {
+    ($ty:ty { $($accessors:tt)* }) => {
+        impl $ty {
+            crate::number::decimal_accessors!(@accessors $($accessors)*);
+        }
+    };
+    (@accessors) => {};
+    (@accessors $accessor:ident: $field:ident?, $($rest:tt)*) => {
+        #[doc = concat!("`", stringify!($field), "` as an exact decimal")]
+        pub fn $accessor(&self) -> crate::prelude::Result<Option<rust_decimal::Decimal>> {
+            self.$field
+                .as_deref()
+                .map(crate::number::parse_decimal)
+                .transpose()
+        }
+
+        crate::number::decimal_accessors!(@accessors $($rest)*);
+    };
+    (@accessors $accessor:ident: $field:ident, $($rest:tt)*) => {
+        #[doc = concat!("`", stringify!($field), "` as an exact decimal")]
+        pub fn $accessor(&self) -> crate::prelude::Result<rust_decimal::Decimal> {
+            crate::number::parse_decimal(&self.$field)
+        }
+
+        crate::number::decimal_accessors!(@accessors $($rest)*);
+    };
+}
+#[cfg(feature = "rust_decimal")]
+pub(crate) use decimal_accessors;
+
+#[cfg(all(test, feature = "rust_decimal"))]
+mod tests {
+    use super::*;
+    use crate::{FilledOrder, Leverage, PositionData};
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_decimal_accessors() -> Result<()> {
+        let filled = FilledOrder {
+            total_sz: "0.30000".to_string(),
+            avg_px: "1891.4".to_string(),
+            oid: 1,
+        };
+        assert_eq!(filled.total_sz_decimal()?, Decimal::new(3, 1));
+        assert_eq!(filled.avg_px_decimal()?, Decimal::new(18914, 1));
+
+        let position = PositionData {
+            coin: "ETH".to_string(),
+            entry_px: Some("1891.4".to_string()),
+            leverage: Leverage {
+                type_string: "cross".to_string(),
+                value: 20,
+                raw_usd: None,
+            },
+            liquidation_px: None,
+            margin_used: "28.37".to_string(),
+            position_value: "567.42".to_string(),
+            return_on_equity: "0.0".to_string(),
+            szi: "-0.3".to_string(),
+            unrealized_pnl: "not a number".to_string(),
+        };
+        assert_eq!(position.entry_px_decimal()?, Some(Decimal::new(18914, 1)));
+        assert_eq!(position.liquidation_px_decimal()?, None);
+        assert_eq!(position.szi_decimal()?, Decimal::new(-3, 1));
+        assert!(position.unrealized_pnl_decimal().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_decimal_to_wire() {
+        let wire = |s: &str| decimal_to_wire(s.parse().unwrap());
+        assert_eq!(wire("0.00076000"), "0.00076");
+        assert_eq!(wire("-0.0000"), "0");
+        assert_eq!(wire("1891.40"), "1891.4");
+        assert_eq!(wire("987654321.00000000"), "987654321");
+        assert_eq!(wire("0.123456789"), "0.12345679");
+        assert_eq!(wire("87654321.12345678"), "87654321.12345678");
+    }
+}
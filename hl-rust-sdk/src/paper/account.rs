@@ -0,0 +1,243 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::{json, Value};
+
+use crate::{consts::EPSILON, paper::book::Book, paper::wire};
+
+/// Leverage of coins the user never updated it for
+const DEFAULT_LEVERAGE: u32 = 20;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct Position {
+    /// Signed size, negative for shorts
+    pub(super) szi: f64,
+    pub(super) entry_px: f64,
+}
+
+/// Change a fill made to the position it traded
+#[derive(Debug, Clone)]
+pub(super) struct FillEffect {
+    pub(super) start_position: f64,
+    pub(super) closed_pnl: f64,
+    pub(super) dir: &'static str,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct Account {
+    /// USDC of the perp account, including realized pnl and fees paid
+    pub(super) balance: f64,
+    /// USDC of the spot account
+    pub(super) spot_usdc: f64,
+    pub(super) positions: BTreeMap<String, Position>,
+    /// Leverage and whether it's cross, by coin
+    pub(super) leverage: HashMap<String, (u32, bool)>,
+    /// Fills as returned by `userFills`, oldest first
+    pub(super) fills: Vec<Value>,
+}
+
+fn mark_px(books: &HashMap<String, Book>, coin: &str, position: &Position) -> f64 {
+    books
+        .get(coin)
+        .and_then(Book::mid)
+        .unwrap_or(position.entry_px)
+}
+
+impl Account {
+    pub(super) fn leverage(&self, coin: &str) -> (u32, bool) {
+        self.leverage
+            .get(coin)
+            .copied()
+            .unwrap_or((DEFAULT_LEVERAGE, true))
+    }
+
+    pub(super) fn szi(&self, coin: &str) -> f64 {
+        self.positions
+            .get(coin)
+            .map_or(0.0, |position| position.szi)
+    }
+
+    /// Updates the position in `coin` and the balance for a fill, and returns what it changed
+    pub(super) fn apply_fill(
+        &mut self,
+        coin: &str,
+        is_buy: bool,
+        px: f64,
+        sz: f64,
+        fee: f64,
+    ) -> FillEffect {
+        let position = self.positions.entry(coin.to_string()).or_default();
+        let start_position = position.szi;
+        let signed_sz = if is_buy { sz } else { -sz };
+
+        let mut closed_pnl = 0.0;
+        let dir = if start_position.abs() < EPSILON || (start_position > 0.0) == is_buy {
+            let new_sz = start_position.abs() + sz;
+            position.entry_px = (position.entry_px * start_position.abs() + px * sz) / new_sz;
+            if is_buy {
+                "Open Long"
+            } else {
+                "Open Short"
+            }
+        } else {
+            let closed = sz.min(start_position.abs());
+            closed_pnl = closed * (px - position.entry_px) * start_position.signum();
+            if sz > start_position.abs() + EPSILON {
+                // Flipped, so the rest opened a new position at the fill price
+                position.entry_px = px;
+                if is_buy {
+                    "Short > Long"
+                } else {
+                    "Long > Short"
+                }
+            } else if is_buy {
+                "Close Short"
+            } else {
+                "Close Long"
+            }
+        };
+
+        position.szi = start_position + signed_sz;
+        if position.szi.abs() < EPSILON {
+            self.positions.remove(coin);
+        }
+        self.balance += closed_pnl - fee;
+
+        FillEffect {
+            start_position,
+            closed_pnl,
+            dir,
+        }
+    }
+
+    pub(super) fn unrealized_pnl(&self, books: &HashMap<String, Book>) -> f64 {
+        self.positions
+            .iter()
+            .map(|(coin, position)| {
+                position.szi * (mark_px(books, coin, position) - position.entry_px)
+            })
+            .sum()
+    }
+
+    pub(super) fn account_value(&self, books: &HashMap<String, Book>) -> f64 {
+        self.balance + self.unrealized_pnl(books)
+    }
+
+    pub(super) fn margin_used(&self, books: &HashMap<String, Book>) -> f64 {
+        self.positions
+            .iter()
+            .map(|(coin, position)| {
+                position.szi.abs() * mark_px(books, coin, position) / self.leverage(coin).0 as f64
+            })
+            .sum()
+    }
+
+    /// Perp USDC that can be transferred out without touching the margin of positions
+    pub(super) fn withdrawable(&self, books: &HashMap<String, Book>) -> f64 {
+        (self.account_value(books) - self.margin_used(books)).max(0.0)
+    }
+
+    /// Account as returned by `clearinghouseState`
+    pub(super) fn user_state(&self, books: &HashMap<String, Book>) -> Value {
+        let asset_positions: Vec<Value> = self
+            .positions
+            .iter()
+            .map(|(coin, position)| {
+                let (leverage, is_cross) = self.leverage(coin);
+                let mark_px = mark_px(books, coin, position);
+                let position_value = position.szi.abs() * mark_px;
+                let margin_used = position_value / leverage as f64;
+                let unrealized_pnl = position.szi * (mark_px - position.entry_px);
+                let return_on_equity = if margin_used < EPSILON {
+                    "0".to_string()
+                } else {
+                    wire(unrealized_pnl / margin_used)
+                };
+                json!({
+                    "type": "oneWay",
+                    "position": {
+                        "coin": coin,
+                        "entryPx": wire(position.entry_px),
+                        "leverage": {
+                            "type": if is_cross { "cross" } else { "isolated" },
+                            "value": leverage,
+                            "rawUsd": null
+                        },
+                        "liquidationPx": null,
+                        "marginUsed": wire(margin_used),
+                        "positionValue": wire(position_value),
+                        "returnOnEquity": return_on_equity,
+                        "szi": wire(position.szi),
+                        "unrealizedPnl": wire(unrealized_pnl)
+                    }
+                })
+            })
+            .collect();
+
+        let total_ntl_pos: f64 = self
+            .positions
+            .iter()
+            .map(|(coin, position)| position.szi.abs() * mark_px(books, coin, position))
+            .sum();
+        let total_raw_usd: f64 = self.balance
+            - self
+                .positions
+                .values()
+                .map(|position| position.szi * position.entry_px)
+                .sum::<f64>();
+        let margin_summary = json!({
+            "accountValue": wire(self.account_value(books)),
+            "totalMarginUsed": wire(self.margin_used(books)),
+            "totalNtlPos": wire(total_ntl_pos),
+            "totalRawUsd": wire(total_raw_usd)
+        });
+
+        json!({
+            "assetPositions": asset_positions,
+            "marginSummary": margin_summary,
+            "crossMarginSummary": margin_summary,
+            "withdrawable": wire(self.withdrawable(books))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_fill_tracks_entry_and_pnl() {
+        let mut account = Account {
+            balance: 1000.0,
+            ..Account::default()
+        };
+
+        let effect = account.apply_fill("ETH", true, 100.0, 1.0, 0.0);
+        assert_eq!(effect.dir, "Open Long");
+        account.apply_fill("ETH", true, 110.0, 1.0, 0.0);
+        assert!((account.positions["ETH"].entry_px - 105.0).abs() < EPSILON);
+
+        let effect = account.apply_fill("ETH", false, 115.0, 3.0, 0.5);
+        assert_eq!(effect.dir, "Long > Short");
+        assert!((effect.start_position - 2.0).abs() < EPSILON);
+        assert!((effect.closed_pnl - 20.0).abs() < EPSILON);
+        assert!((account.szi("ETH") + 1.0).abs() < EPSILON);
+        assert!((account.positions["ETH"].entry_px - 115.0).abs() < EPSILON);
+        assert!((account.balance - 1019.5).abs() < EPSILON);
+
+        let effect = account.apply_fill("ETH", true, 120.0, 1.0, 0.0);
+        assert_eq!(effect.dir, "Close Short");
+        assert!((effect.closed_pnl + 5.0).abs() < EPSILON);
+        assert!(account.positions.is_empty());
+    }
+
+    #[test]
+    fn test_user_state_without_margin() {
+        let mut account = Account::default();
+        account.apply_fill("ETH", true, 0.0, 1.0, 0.0);
+
+        let user_state = account.user_state(&HashMap::new());
+        let position = &user_state["assetPositions"][0]["position"];
+        assert_eq!(position["marginUsed"], json!("0"));
+        assert_eq!(position["returnOnEquity"], json!("0"));
+    }
+}
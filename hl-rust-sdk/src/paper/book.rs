@@ -0,0 +1,166 @@
+use serde_json::{json, Value};
+
+use crate::{
+    consts::EPSILON, number::parse_number, paper::wire, prelude::*, L2BookData, L2SnapshotResponse,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub(super) struct PriceLevel {
+    pub(super) px: f64,
+    pub(super) sz: f64,
+    pub(super) n: u64,
+}
+
+/// Last L2 book fed for a coin, minus what paper orders took from it since
+#[derive(Debug, Clone, Default)]
+pub(super) struct Book {
+    /// Best first, as in the `levels` of the exchange
+    pub(super) bids: Vec<PriceLevel>,
+    pub(super) asks: Vec<PriceLevel>,
+    pub(super) time: u64,
+}
+
+impl Book {
+    pub(super) fn from_snapshot(snapshot: &L2SnapshotResponse) -> Result<Book> {
+        let side = |i: usize| -> Result<Vec<PriceLevel>> {
+            snapshot
+                .levels
+                .get(i)
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+                .iter()
+                .map(|level| {
+                    Ok(PriceLevel {
+                        px: parse_number(&level.px)?,
+                        sz: parse_number(&level.sz)?,
+                        n: level.n,
+                    })
+                })
+                .collect()
+        };
+        Ok(Book {
+            bids: side(0)?,
+            asks: side(1)?,
+            time: snapshot.time,
+        })
+    }
+
+    pub(super) fn from_ws(data: &L2BookData) -> Result<Book> {
+        let side = |i: usize| -> Result<Vec<PriceLevel>> {
+            data.levels
+                .get(i)
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+                .iter()
+                .map(|level| {
+                    Ok(PriceLevel {
+                        px: parse_number(&level.px)?,
+                        sz: parse_number(&level.sz)?,
+                        n: level.n,
+                    })
+                })
+                .collect()
+        };
+        Ok(Book {
+            bids: side(0)?,
+            asks: side(1)?,
+            time: data.time,
+        })
+    }
+
+    pub(super) fn mid(&self) -> Option<f64> {
+        match (self.bids.first(), self.asks.first()) {
+            (Some(bid), Some(ask)) => Some((bid.px + ask.px) / 2.0),
+            (Some(level), None) | (None, Some(level)) => Some(level.px),
+            (None, None) => None,
+        }
+    }
+
+    /// Whether an order on `is_buy` at `limit_px` would match the other side of the book
+    pub(super) fn crosses(&self, is_buy: bool, limit_px: f64) -> bool {
+        if is_buy {
+            self.asks.first().is_some_and(|ask| ask.px <= limit_px)
+        } else {
+            self.bids.first().is_some_and(|bid| bid.px >= limit_px)
+        }
+    }
+
+    /// Takes up to `sz` from the levels an order on `is_buy` at `limit_px` matches, best first,
+    /// and returns the price and size taken from each
+    pub(super) fn take(&mut self, is_buy: bool, limit_px: f64, sz: f64) -> Vec<(f64, f64)> {
+        let levels = if is_buy {
+            &mut self.asks
+        } else {
+            &mut self.bids
+        };
+        let mut remaining = sz;
+        let mut fills = Vec::new();
+        while remaining > EPSILON {
+            let Some(level) = levels.first_mut() else {
+                break;
+            };
+            let crosses = if is_buy {
+                level.px <= limit_px
+            } else {
+                level.px >= limit_px
+            };
+            if !crosses {
+                break;
+            }
+            let taken = remaining.min(level.sz);
+            fills.push((level.px, taken));
+            remaining -= taken;
+            level.sz -= taken;
+            if level.sz <= EPSILON {
+                levels.remove(0);
+            }
+        }
+        fills
+    }
+
+    /// Book as sent by `l2Book` requests and subscriptions
+    pub(super) fn to_json(&self, coin: &str) -> Value {
+        let side = |levels: &[PriceLevel]| -> Vec<Value> {
+            levels
+                .iter()
+                .map(|level| json!({"px": wire(level.px), "sz": wire(level.sz), "n": level.n}))
+                .collect()
+        };
+        json!({
+            "coin": coin,
+            "time": self.time,
+            "levels": [side(&self.bids), side(&self.asks)]
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(px: f64, sz: f64) -> PriceLevel {
+        PriceLevel { px, sz, n: 1 }
+    }
+
+    #[test]
+    fn test_take_walks_the_book() {
+        let mut book = Book {
+            bids: vec![level(99.0, 1.0)],
+            asks: vec![level(101.0, 1.0), level(102.0, 2.0), level(103.0, 5.0)],
+            time: 0,
+        };
+        assert!(book.crosses(true, 101.0));
+        assert!(!book.crosses(false, 100.0));
+
+        let fills = book.take(true, 102.0, 2.5);
+        assert_eq!(fills, vec![(101.0, 1.0), (102.0, 1.5)]);
+        assert_eq!(book.asks.len(), 2);
+        assert!((book.asks[0].sz - 0.5).abs() < EPSILON);
+        assert_eq!(book.mid(), Some(100.5));
+
+        // Nothing left at or below the limit
+        let fills = book.take(true, 102.0, 1.0);
+        assert_eq!(fills, vec![(102.0, 0.5)]);
+        assert!(book.take(true, 102.0, 1.0).is_empty());
+    }
+}
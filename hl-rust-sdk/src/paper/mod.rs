@@ -0,0 +1,1301 @@
+//! Paper trading against live or recorded books. `PaperExchange` answers `/info` and
+//! `/exchange` requests the way the API does, so clients built with `with_transport` trade
+//! against it unchanged, and it serves their subscriptions to fills, order updates and books.
+
+mod account;
+mod book;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Mutex, MutexGuard},
+};
+
+use async_trait::async_trait;
+use ethers::types::{H160, H256};
+use log::error;
+use serde_json::{json, Value};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    consts::EPSILON,
+    exchange::{OidOrCloid, OrderRequest},
+    helpers::{float_to_string_for_hashing, now_timestamp_ms},
+    prelude::*,
+    recover_signer, Actions, AssetSnapshot, Error, ExchangePayload, InfoClient, InfoRequest,
+    L2SnapshotResponse, Message, Meta, Order, SpotMeta, Subscription, Tif, Transport,
+};
+use account::Account;
+use book::Book;
+
+/// Fee rates of the base tier
+const DEFAULT_TAKER_FEE: f64 = 0.00045;
+const DEFAULT_MAKER_FEE: f64 = 0.00015;
+
+/// Number as the exchange sends it
+fn wire(x: f64) -> String {
+    float_to_string_for_hashing(x)
+}
+
+/// Simulated exchange for paper trading, to be used as the `Transport` of an `InfoClient` and
+/// `ExchangeClient`.
+///
+/// Orders on perps match against the L2 books fed with `feed_l2_snapshot` or `feed_message`,
+/// taking liquidity up to their limit price. Gtc and Alo orders rest with what's left and fill
+/// at their limit price once a later book crosses them. Paper orders don't match each other and
+/// aren't shown in the books. TWAPs execute at once against the book, so they're finished by the
+/// time they could be canceled.
+///
+/// Supports order, cancel, modify, leverage, USDC transfer, withdraw, approve agent and TWAP
+/// actions; others get an error response. Accounts start empty and are funded with `deposit`.
+pub struct PaperExchange {
+    /// Network the clients sign for, which is needed to recover who sent L1 actions
+    pub is_mainnet: bool,
+    /// Fee rate charged on the notional of fills taking liquidity
+    pub taker_fee: f64,
+    /// Fee rate charged on the notional of fills of resting orders
+    pub maker_fee: f64,
+    state: Mutex<PaperState>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Fees {
+    taker: f64,
+    maker: f64,
+}
+
+/// What a subscription served by the paper exchange receives
+#[derive(Debug)]
+enum Feed {
+    UserEvents(H160),
+    UserFills(H160),
+    OrderUpdates(H160),
+    L2Book(String),
+    AllMids,
+}
+
+#[derive(Debug, Clone)]
+struct PaperOrder {
+    user: H160,
+    asset: u32,
+    coin: String,
+    is_buy: bool,
+    limit_px: f64,
+    /// Size left to fill
+    sz: f64,
+    orig_sz: f64,
+    reduce_only: bool,
+    tif: Tif,
+    cloid: Option<String>,
+    timestamp: u64,
+    status: &'static str,
+    status_timestamp: u64,
+}
+
+impl PaperOrder {
+    fn side(&self) -> &'static str {
+        if self.is_buy {
+            "B"
+        } else {
+            "A"
+        }
+    }
+
+    fn to_json(&self, oid: u64) -> Value {
+        json!({
+            "coin": self.coin,
+            "side": self.side(),
+            "limitPx": wire(self.limit_px),
+            "sz": wire(self.sz),
+            "oid": oid,
+            "timestamp": self.timestamp,
+            "origSz": wire(self.orig_sz),
+            "cloid": self.cloid
+        })
+    }
+
+    fn is_open(&self) -> bool {
+        self.status == "open"
+    }
+}
+
+/// Order of an action, with the prices and sizes parsed
+struct NewOrder {
+    asset: u32,
+    is_buy: bool,
+    limit_px: f64,
+    sz: f64,
+    reduce_only: bool,
+    tif: Tif,
+    cloid: Option<String>,
+}
+
+impl NewOrder {
+    fn from_request(order: &OrderRequest) -> std::result::Result<NewOrder, String> {
+        let asset = order.asset;
+        let Order::Limit(limit) = &order.order_type else {
+            return Err(format!(
+                "Trigger orders are not supported by the paper exchange. asset={asset}"
+            ));
+        };
+        let limit_px = order
+            .limit_px
+            .parse()
+            .ok()
+            .filter(|px: &f64| *px > 0.0)
+            .ok_or_else(|| format!("Order has invalid price. asset={asset}"))?;
+        let sz = order
+            .sz
+            .parse()
+            .ok()
+            .filter(|sz: &f64| *sz > 0.0)
+            .ok_or_else(|| format!("Order has invalid size. asset={asset}"))?;
+        Ok(NewOrder {
+            asset,
+            is_buy: order.is_buy,
+            limit_px,
+            sz,
+            reduce_only: order.reduce_only,
+            tif: limit.tif,
+            cloid: order.cloid.clone(),
+        })
+    }
+}
+
+struct PaperState {
+    meta: Meta,
+    spot_meta: SpotMeta,
+    assets: AssetSnapshot,
+    books: HashMap<String, Book>,
+    accounts: HashMap<H160, Account>,
+    /// Users of approved agents, by agent
+    agents: HashMap<H160, H160>,
+    /// By oid, so in the order they were placed
+    orders: BTreeMap<u64, PaperOrder>,
+    feeds: Vec<(u32, Feed, UnboundedSender<Message>)>,
+    next_id: u64,
+    next_subscription_id: u32,
+    /// Time of the latest book fed
+    time: u64,
+}
+
+impl PaperExchange {
+    pub fn new(meta: Meta, spot_meta: SpotMeta) -> PaperExchange {
+        let assets = AssetSnapshot::new(meta.clone(), spot_meta.clone());
+        PaperExchange {
+            is_mainnet: false,
+            taker_fee: DEFAULT_TAKER_FEE,
+            maker_fee: DEFAULT_MAKER_FEE,
+            state: Mutex::new(PaperState {
+                meta,
+                spot_meta,
+                assets,
+                books: HashMap::new(),
+                accounts: HashMap::new(),
+                agents: HashMap::new(),
+                orders: BTreeMap::new(),
+                feeds: Vec::new(),
+                next_id: 0,
+                next_subscription_id: 0,
+                time: 0,
+            }),
+        }
+    }
+
+    /// Starts from the metadata, and the current books of `coins`, of the network `info_client`
+    /// queries. Expects clients to sign for the same network.
+    pub async fn from_info_client(
+        info_client: &InfoClient,
+        coins: &[&str],
+    ) -> Result<PaperExchange> {
+        let mut paper_exchange =
+            PaperExchange::new(info_client.meta().await?, info_client.spot_meta().await?);
+        paper_exchange.is_mainnet = info_client.http_client.base_url.is_mainnet();
+        for coin in coins {
+            let snapshot = info_client.l2_snapshot(coin.to_string()).await?;
+            paper_exchange.feed_l2_snapshot(&snapshot)?;
+        }
+        Ok(paper_exchange)
+    }
+
+    /// Replaces the book of the coin, filling the resting orders it crosses
+    pub fn feed_l2_snapshot(&self, snapshot: &L2SnapshotResponse) -> Result<()> {
+        let book = Book::from_snapshot(snapshot)?;
+        self.state().feed(&snapshot.coin, book, self.fees());
+        Ok(())
+    }
+
+    /// Feeds the book of a `Message::L2Book`, e.g. from a live subscription or a recording.
+    /// Other messages are ignored.
+    pub fn feed_message(&self, message: &Message) -> Result<()> {
+        if let Message::L2Book(l2_book) = message {
+            let book = Book::from_ws(&l2_book.data)?;
+            self.state().feed(&l2_book.data.coin, book, self.fees());
+        }
+        Ok(())
+    }
+
+    /// Credits `usdc` to the perp account of `user`
+    pub fn deposit(&self, user: H160, usdc: f64) {
+        self.state().accounts.entry(user).or_default().balance += usdc;
+    }
+
+    /// Lets `agent` trade for `user`, like an agent approved before the simulation started
+    pub fn add_agent(&self, user: H160, agent: H160) {
+        self.state().agents.insert(agent, user);
+    }
+
+    fn state(&self) -> MutexGuard<'_, PaperState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn fees(&self) -> Fees {
+        Fees {
+            taker: self.taker_fee,
+            maker: self.maker_fee,
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for PaperExchange {
+    async fn post(&self, url_path: &str, body: String) -> Result<String> {
+        let response = match url_path {
+            "/info" => {
+                let request: InfoRequest =
+                    serde_json::from_str(&body).map_err(|e| Error::JsonParse(e.to_string()))?;
+                self.state().info(request)?
+            }
+            "/exchange" => {
+                let payload: ExchangePayload =
+                    serde_json::from_str(&body).map_err(|e| Error::JsonParse(e.to_string()))?;
+                let signer = recover_signer(&payload, self.is_mainnet)?;
+                let response = self.state().exchange(&payload, signer, self.fees());
+                match response {
+                    Ok(response) => json!({"status": "ok", "response": response}),
+                    Err(message) => json!({"status": "err", "response": message}),
+                }
+            }
+            _ => {
+                return Err(Error::GenericRequest(format!(
+                    "unexpected url path {url_path}"
+                )))
+            }
+        };
+        serde_json::to_string(&response).map_err(|e| Error::JsonParse(e.to_string()))
+    }
+
+    fn subscribe(
+        &self,
+        subscription: &Subscription,
+        sender_channel: UnboundedSender<Message>,
+    ) -> Option<Result<u32>> {
+        let feed = match subscription {
+            Subscription::UserEvents { user } => Feed::UserEvents(*user),
+            Subscription::UserFills { user } => Feed::UserFills(*user),
+            Subscription::OrderUpdates { user } => Feed::OrderUpdates(*user),
+            Subscription::L2Book { coin } => Feed::L2Book(coin.clone()),
+            Subscription::AllMids => Feed::AllMids,
+            subscription => {
+                return Some(Err(Error::GenericRequest(format!(
+                    "{subscription:?} isn't served by the paper exchange"
+                ))))
+            }
+        };
+        let mut state = self.state();
+        state.next_subscription_id += 1;
+        let subscription_id = state.next_subscription_id;
+        state.feeds.push((subscription_id, feed, sender_channel));
+        Some(Ok(subscription_id))
+    }
+
+    fn unsubscribe(&self, subscription_id: u32) -> Option<Result<()>> {
+        let mut state = self.state();
+        let subscriptions = state.feeds.len();
+        state.feeds.retain(|(id, _, _)| *id != subscription_id);
+        if state.feeds.len() == subscriptions {
+            return Some(Err(Error::SubscriptionNotFound));
+        }
+        Some(Ok(()))
+    }
+}
+
+impl PaperState {
+    fn now(&self) -> u64 {
+        if self.time > 0 {
+            self.time
+        } else {
+            now_timestamp_ms()
+        }
+    }
+
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    fn all_mids(&self) -> HashMap<String, String> {
+        self.books
+            .iter()
+            .filter_map(|(coin, book)| Some((coin.clone(), wire(book.mid()?))))
+            .collect()
+    }
+
+    fn info(&self, request: InfoRequest) -> Result<Value> {
+        let empty = Account::default();
+        let account = |user: &H160| self.accounts.get(user).unwrap_or(&empty);
+        let response = match request {
+            InfoRequest::Meta => json!(self.meta),
+            InfoRequest::SpotMeta => json!(self.spot_meta),
+            InfoRequest::AllMids => json!(self.all_mids()),
+            InfoRequest::L2Book { coin } => self
+                .books
+                .get(&coin)
+                .map_or(Value::Null, |book| book.to_json(&coin)),
+            InfoRequest::UserState { user } => account(&user).user_state(&self.books),
+            InfoRequest::UserTokenBalances { user } => json!({
+                "balances": [{
+                    "coin": "USDC",
+                    "token": 0,
+                    "hold": "0",
+                    "total": wire(account(&user).spot_usdc),
+                    "entryNtl": "0"
+                }]
+            }),
+            InfoRequest::OpenOrders { user } => self
+                .orders
+                .iter()
+                .filter(|(_, order)| order.user == user && order.is_open())
+                .map(|(oid, order)| order.to_json(*oid))
+                .collect(),
+            InfoRequest::UserFills { user } => account(&user).fills.iter().rev().cloned().collect(),
+            InfoRequest::OrderStatus { user, oid } => match self.orders.get(&oid) {
+                Some(order) if order.user == user => {
+                    let mut basic_order = order.to_json(oid);
+                    let details = json!({
+                        "triggerCondition": "N/A",
+                        "isTrigger": false,
+                        "triggerPx": "0",
+                        "isPositionTpsl": false,
+                        "reduceOnly": order.reduce_only,
+                        "orderType": "Limit",
+                        "tif": format!("{:?}", order.tif)
+                    });
+                    if let (Some(basic_order), Value::Object(details)) =
+                        (basic_order.as_object_mut(), details)
+                    {
+                        basic_order.extend(details);
+                    }
+                    json!({
+                        "status": "order",
+                        "order": {
+                            "order": basic_order,
+                            "status": order.status,
+                            "statusTimestamp": order.status_timestamp
+                        }
+                    })
+                }
+                _ => json!({"status": "unknownOid"}),
+            },
+            request => {
+                return Err(Error::GenericRequest(format!(
+                    "{request:?} isn't served by the paper exchange"
+                )))
+            }
+        };
+        Ok(response)
+    }
+
+    /// Response of a request to `/exchange` signed by `signer`
+    fn exchange(
+        &mut self,
+        payload: &ExchangePayload,
+        signer: H160,
+        fees: Fees,
+    ) -> std::result::Result<Value, String> {
+        let action: Actions =
+            serde_json::from_value(payload.action.clone()).map_err(|e| e.to_string())?;
+        let user = payload
+            .vault_address
+            .unwrap_or_else(|| self.agents.get(&signer).copied().unwrap_or(signer));
+        let default = json!({"type": "default"});
+
+        match action {
+            Actions::Order(bulk_order) => {
+                let statuses: Vec<Value> = bulk_order
+                    .orders
+                    .iter()
+                    .map(|order| self.place_order(user, NewOrder::from_request(order), fees))
+                    .collect();
+                Ok(json!({"type": "order", "data": {"statuses": statuses}}))
+            }
+            Actions::Cancel(bulk_cancel) => {
+                let statuses: Vec<Value> = bulk_cancel
+                    .cancels
+                    .iter()
+                    .map(|cancel| self.cancel(user, cancel.asset, |oid, _| oid == cancel.oid))
+                    .collect();
+                Ok(json!({"type": "cancel", "data": {"statuses": statuses}}))
+            }
+            Actions::CancelByCloid(bulk_cancel) => {
+                let statuses: Vec<Value> = bulk_cancel
+                    .cancels
+                    .iter()
+                    .map(|cancel| {
+                        self.cancel(user, cancel.asset, |_, order| {
+                            order
+                                .cloid
+                                .as_ref()
+                                .is_some_and(|cloid| cloid.eq_ignore_ascii_case(&cancel.cloid))
+                        })
+                    })
+                    .collect();
+                Ok(json!({"type": "cancelByCloid", "data": {"statuses": statuses}}))
+            }
+            Actions::Modify(modify) => {
+                let status = self.modify(user, &modify.oid, &modify.order, fees);
+                match status.get("error").and_then(Value::as_str) {
+                    Some(message) => Err(message.to_string()),
+                    None => Ok(default),
+                }
+            }
+            Actions::BatchModify(bulk_modify) => {
+                let statuses: Vec<Value> = bulk_modify
+                    .modifies
+                    .iter()
+                    .map(|modify| self.modify(user, &modify.oid, &modify.order, fees))
+                    .collect();
+                Ok(json!({"type": "order", "data": {"statuses": statuses}}))
+            }
+            Actions::UpdateLeverage(update_leverage) => {
+                let coin = self
+                    .perp_coin(update_leverage.asset)
+                    .map_err(|e| e.to_string())?;
+                if update_leverage.leverage == 0 {
+                    return Err(format!(
+                        "Invalid leverage value {}",
+                        update_leverage.leverage
+                    ));
+                }
+                self.accounts
+                    .entry(user)
+                    .or_default()
+                    .leverage
+                    .insert(coin, (update_leverage.leverage, update_leverage.is_cross));
+                Ok(default)
+            }
+            Actions::UsdSend(usd_send) => {
+                let destination: H160 = usd_send
+                    .destination
+                    .parse()
+                    .map_err(|_| format!("Invalid destination {}", usd_send.destination))?;
+                let amount = parse_amount(&usd_send.amount)?;
+                self.withdraw(user, amount)?;
+                self.accounts.entry(destination).or_default().balance += amount;
+                Ok(default)
+            }
+            Actions::Withdraw3(withdraw) => {
+                self.withdraw(signer, parse_amount(&withdraw.amount)?)?;
+                Ok(default)
+            }
+            Actions::SpotUser(spot_user) => {
+                let usdc = spot_user.class_transfer.usdc as f64 / 1e6;
+                if spot_user.class_transfer.to_perp {
+                    let account = self.accounts.entry(user).or_default();
+                    if usdc > account.spot_usdc + EPSILON {
+                        return Err("Insufficient spot balance for transfer".to_string());
+                    }
+                    account.spot_usdc -= usdc;
+                    account.balance += usdc;
+                } else {
+                    self.withdraw(user, usdc)?;
+                    self.accounts.entry(user).or_default().spot_usdc += usdc;
+                }
+                Ok(default)
+            }
+            Actions::ApproveAgent(approve_agent) => {
+                self.agents.insert(approve_agent.agent_address, signer);
+                Ok(default)
+            }
+            Actions::TwapOrder(twap_order) => {
+                let twap = twap_order.twap;
+                let order = twap
+                    .sz
+                    .parse()
+                    .ok()
+                    .filter(|sz: &f64| *sz > 0.0)
+                    .map(|sz| NewOrder {
+                        asset: twap.asset,
+                        is_buy: twap.is_buy,
+                        limit_px: if twap.is_buy { f64::MAX } else { 0.0 },
+                        sz,
+                        reduce_only: twap.reduce_only,
+                        tif: Tif::Ioc,
+                        cloid: None,
+                    })
+                    .ok_or_else(|| format!("Order has invalid size. asset={}", twap.asset));
+                let status = match self.place_order(user, order, fees) {
+                    Value::Object(status) if status.contains_key("error") => Value::Object(status),
+                    _ => json!({"running": {"twapId": self.next_id()}}),
+                };
+                Ok(json!({"type": "twapOrder", "data": {"status": status}}))
+            }
+            Actions::TwapCancel(_) => {
+                let status =
+                    json!({"error": "TWAP was never placed, already canceled, or filled."});
+                Ok(json!({"type": "twapCancel", "data": {"status": status}}))
+            }
+            _ => Err(format!(
+                "{} actions are not supported by the paper exchange",
+                payload.action["type"]
+            )),
+        }
+    }
+
+    fn perp_coin(&self, asset: u32) -> Result<String> {
+        match self.assets.coin(asset) {
+            Some(coin) if asset < 10000 => Ok(coin.to_string()),
+            Some(_) => Err(Error::GenericRequest(format!(
+                "Spot assets are not supported by the paper exchange. asset={asset}"
+            ))),
+            None => Err(Error::AssetNotFound),
+        }
+    }
+
+    /// Takes `usdc` out of the perp account of `user`, as long as it isn't margin
+    fn withdraw(&mut self, user: H160, usdc: f64) -> std::result::Result<(), String> {
+        let account = self.accounts.entry(user).or_default();
+        if usdc > account.withdrawable(&self.books) + EPSILON {
+            return Err("Insufficient balance for withdrawal".to_string());
+        }
+        account.balance -= usdc;
+        Ok(())
+    }
+
+    /// Status of placing `order`, in the form of the statuses of an order response
+    fn place_order(
+        &mut self,
+        user: H160,
+        order: std::result::Result<NewOrder, String>,
+        fees: Fees,
+    ) -> Value {
+        match order.and_then(|order| self.try_place_order(user, order, fees)) {
+            Ok(status) => status,
+            Err(message) => json!({"error": message}),
+        }
+    }
+
+    fn try_place_order(
+        &mut self,
+        user: H160,
+        order: NewOrder,
+        fees: Fees,
+    ) -> std::result::Result<Value, String> {
+        let asset = order.asset;
+        let coin = self.perp_coin(asset).map_err(|e| e.to_string())?;
+        let book = self.books.get(&coin).cloned().unwrap_or_default();
+
+        let szi = self
+            .accounts
+            .get(&user)
+            .map_or(0.0, |account| account.szi(&coin));
+        let sz = if order.reduce_only {
+            if szi.abs() < EPSILON || (szi > 0.0) == order.is_buy {
+                return Err(format!(
+                    "Reduce only order would increase position. asset={asset}"
+                ));
+            }
+            order.sz.min(szi.abs())
+        } else {
+            order.sz
+        };
+
+        let takes_only = matches!(order.tif, Tif::Ioc | Tif::FrontendMarket);
+        let crosses = book.crosses(order.is_buy, order.limit_px);
+        if takes_only && !crosses {
+            return Err(format!(
+                "Order could not immediately match against any resting orders. asset={asset}"
+            ));
+        }
+        if order.tif == Tif::Alo && crosses {
+            let best_px = |levels: &[book::PriceLevel]| {
+                levels
+                    .first()
+                    .map_or("0".to_string(), |level| wire(level.px))
+            };
+            return Err(format!(
+                "Post only order would have immediately matched, bbo was {}@{}. asset={asset}",
+                best_px(&book.bids),
+                best_px(&book.asks)
+            ));
+        }
+        if !order.reduce_only && !self.has_margin(user, &coin, &order, sz) {
+            return Err(format!("Insufficient margin to place order. asset={asset}"));
+        }
+
+        let fills = match self.books.get_mut(&coin) {
+            Some(book) if order.tif != Tif::Alo => book.take(order.is_buy, order.limit_px, sz),
+            _ => Vec::new(),
+        };
+        let oid = self.next_id();
+        let time = self.now();
+        let mut paper_order = PaperOrder {
+            user,
+            asset,
+            coin,
+            is_buy: order.is_buy,
+            limit_px: order.limit_px,
+            sz,
+            orig_sz: sz,
+            reduce_only: order.reduce_only,
+            tif: order.tif,
+            cloid: order.cloid,
+            timestamp: time,
+            status: "open",
+            status_timestamp: time,
+        };
+        let trades = self.record_fills(oid, &paper_order, &fills, true, fees.taker);
+
+        let filled: f64 = fills.iter().map(|(_, sz)| sz).sum();
+        let notional: f64 = fills.iter().map(|(px, sz)| px * sz).sum();
+        paper_order.sz -= filled;
+        let rests = paper_order.sz > EPSILON && !takes_only;
+        if !rests {
+            paper_order.status = "filled";
+        }
+        self.orders.insert(oid, paper_order);
+        self.publish_order_update(oid);
+        self.publish_fills(user, trades);
+
+        if rests {
+            Ok(json!({"resting": {"oid": oid}}))
+        } else {
+            Ok(json!({
+                "filled": {"totalSz": wire(filled), "avgPx": wire(notional / filled), "oid": oid}
+            }))
+        }
+    }
+
+    /// Whether the account of `user` has the margin for `sz` of `order` on top of its positions
+    /// and resting orders
+    fn has_margin(&self, user: H160, coin: &str, order: &NewOrder, sz: f64) -> bool {
+        let Some(account) = self.accounts.get(&user) else {
+            return false;
+        };
+        let szi = account.szi(coin);
+        let increase = if szi.abs() < EPSILON || (szi > 0.0) == order.is_buy {
+            sz
+        } else {
+            (sz - szi.abs()).max(0.0)
+        };
+        // Marketable orders fill around the mid, while others rest at their limit price
+        let px = match self.books.get(coin).and_then(Book::mid) {
+            Some(mid) if order.is_buy => mid.min(order.limit_px),
+            Some(mid) => mid.max(order.limit_px),
+            None => order.limit_px,
+        };
+        let required = increase * px / account.leverage(coin).0 as f64;
+
+        let reserved: f64 = self
+            .orders
+            .values()
+            .filter(|order| order.user == user && order.is_open() && !order.reduce_only)
+            .map(|order| order.sz * order.limit_px / account.leverage(&order.coin).0 as f64)
+            .sum();
+        let available = account.account_value(&self.books) - account.margin_used(&self.books);
+        required <= available - reserved + EPSILON
+    }
+
+    /// Applies fills of `order` as (price, size) to its account and returns them as trades
+    fn record_fills(
+        &mut self,
+        oid: u64,
+        order: &PaperOrder,
+        fills: &[(f64, f64)],
+        crossed: bool,
+        fee_rate: f64,
+    ) -> Vec<Value> {
+        let time = self.now();
+        let mut trades = Vec::new();
+        for &(px, sz) in fills {
+            let tid = self.next_id();
+            let fee = px * sz * fee_rate;
+            let account = self.accounts.entry(order.user).or_default();
+            let effect = account.apply_fill(&order.coin, order.is_buy, px, sz, fee);
+            let trade = json!({
+                "coin": order.coin,
+                "px": wire(px),
+                "sz": wire(sz),
+                "side": order.side(),
+                "time": time,
+                "startPosition": wire(effect.start_position),
+                "dir": effect.dir,
+                "closedPnl": wire(effect.closed_pnl),
+                "hash": format!("{:?}", H256::from_low_u64_be(tid)),
+                "oid": oid,
+                "crossed": crossed,
+                "fee": wire(fee),
+                "feeToken": "USDC",
+                "tid": tid,
+                "cloid": order.cloid
+            });
+            account.fills.push(trade.clone());
+            trades.push(trade);
+        }
+        trades
+    }
+
+    /// Cancels the first open order of `user` on `asset` that `matches`, and returns its status
+    /// in the form of a cancel response
+    fn cancel(
+        &mut self,
+        user: H160,
+        asset: u32,
+        matches: impl Fn(u64, &PaperOrder) -> bool,
+    ) -> Value {
+        let oid = self
+            .orders
+            .iter()
+            .find(|(oid, order)| {
+                order.user == user
+                    && order.asset == asset
+                    && order.is_open()
+                    && matches(**oid, order)
+            })
+            .map(|(oid, _)| *oid);
+        let Some(oid) = oid else {
+            return json!({
+                "error": format!("Order was never placed, already canceled, or filled. asset={asset}")
+            });
+        };
+
+        let time = self.now();
+        if let Some(order) = self.orders.get_mut(&oid) {
+            order.status = "canceled";
+            order.status_timestamp = time;
+        }
+        self.publish_order_update(oid);
+        json!("success")
+    }
+
+    /// Replaces an open order with `order`, which gets a new oid
+    fn modify(
+        &mut self,
+        user: H160,
+        oid_or_cloid: &OidOrCloid,
+        order: &OrderRequest,
+        fees: Fees,
+    ) -> Value {
+        let canceled = self.cancel(user, order.asset, |oid, paper_order| match oid_or_cloid {
+            OidOrCloid::Oid(modified_oid) => oid == *modified_oid,
+            OidOrCloid::Cloid(cloid) => paper_order
+                .cloid
+                .as_ref()
+                .is_some_and(|order_cloid| order_cloid.eq_ignore_ascii_case(cloid)),
+        });
+        if canceled.get("error").is_some() {
+            return canceled;
+        }
+        self.place_order(user, NewOrder::from_request(order), fees)
+    }
+
+    fn feed(&mut self, coin: &str, book: Book, fees: Fees) {
+        self.time = self.time.max(book.time);
+        self.books.insert(coin.to_string(), book);
+        self.fill_resting_orders(coin, fees);
+        self.publish_book(coin);
+    }
+
+    /// Fills resting orders on `coin` the book crosses at their limit price, oldest first.
+    /// Reduce-only orders fill up to the position and are canceled once it's closed.
+    fn fill_resting_orders(&mut self, coin: &str, fees: Fees) {
+        let oids: Vec<u64> = self
+            .orders
+            .iter()
+            .filter(|(_, order)| order.is_open() && order.coin == coin)
+            .map(|(oid, _)| *oid)
+            .collect();
+
+        for oid in oids {
+            let Some(order) = self.orders.get(&oid).cloned() else {
+                continue;
+            };
+            let sz = if order.reduce_only {
+                let Some(sz) = self.reduce_only_sz(&order) else {
+                    self.cancel_reduce_only(oid);
+                    continue;
+                };
+                sz
+            } else {
+                order.sz
+            };
+            let Some(book) = self.books.get_mut(coin) else {
+                continue;
+            };
+            let fills: Vec<(f64, f64)> = book
+                .take(order.is_buy, order.limit_px, sz)
+                .into_iter()
+                .map(|(_, sz)| (order.limit_px, sz))
+                .collect();
+            if fills.is_empty() {
+                continue;
+            }
+
+            let trades = self.record_fills(oid, &order, &fills, false, fees.maker);
+            let time = self.now();
+            let mut filled = false;
+            if let Some(order) = self.orders.get_mut(&oid) {
+                order.sz -= fills.iter().map(|(_, sz)| sz).sum::<f64>();
+                if order.sz <= EPSILON {
+                    order.status = "filled";
+                    order.status_timestamp = time;
+                    filled = true;
+                }
+            }
+            if filled {
+                self.publish_order_update(oid);
+            }
+            self.publish_fills(order.user, trades);
+            if order.reduce_only && !filled && self.reduce_only_sz(&order).is_none() {
+                self.cancel_reduce_only(oid);
+            }
+        }
+    }
+
+    /// Size a reduce-only `order` can fill, capped at the position its user holds on the other
+    /// side, or None if there's no such position left
+    fn reduce_only_sz(&self, order: &PaperOrder) -> Option<f64> {
+        let szi = self
+            .accounts
+            .get(&order.user)
+            .map_or(0.0, |account| account.szi(&order.coin));
+        (szi.abs() >= EPSILON && (szi > 0.0) != order.is_buy).then(|| order.sz.min(szi.abs()))
+    }
+
+    fn cancel_reduce_only(&mut self, oid: u64) {
+        let time = self.now();
+        if let Some(order) = self.orders.get_mut(&oid) {
+            order.status = "reduceOnlyCanceled";
+            order.status_timestamp = time;
+        }
+        self.publish_order_update(oid);
+    }
+
+    /// Sends `message` to the subscriptions whose feed `matches`, dropping closed ones
+    fn publish(&mut self, matches: impl Fn(&Feed) -> bool, message: Value) {
+        let message: Message = match serde_json::from_value(message) {
+            Ok(message) => message,
+            Err(e) => {
+                error!("Could not build paper exchange message: {e}");
+                return;
+            }
+        };
+        self.feeds
+            .retain(|(_, feed, sender)| !matches(feed) || sender.send(message.clone()).is_ok());
+    }
+
+    fn publish_fills(&mut self, user: H160, fills: Vec<Value>) {
+        if fills.is_empty() {
+            return;
+        }
+        self.publish(
+            |feed| matches!(feed, Feed::UserEvents(feed_user) if *feed_user == user),
+            json!({"channel": "user", "data": {"fills": fills}}),
+        );
+        self.publish(
+            |feed| matches!(feed, Feed::UserFills(feed_user) if *feed_user == user),
+            json!({"channel": "userFills", "data": {"user": user, "fills": fills}}),
+        );
+    }
+
+    fn publish_order_update(&mut self, oid: u64) {
+        let Some(order) = self.orders.get(&oid) else {
+            return;
+        };
+        let user = order.user;
+        let update = json!({
+            "order": order.to_json(oid),
+            "status": order.status,
+            "statusTimestamp": order.status_timestamp
+        });
+        self.publish(
+            |feed| matches!(feed, Feed::OrderUpdates(feed_user) if *feed_user == user),
+            json!({"channel": "orderUpdates", "data": [update]}),
+        );
+    }
+
+    fn publish_book(&mut self, coin: &str) {
+        if let Some(book) = self.books.get(coin) {
+            let data = book.to_json(coin);
+            self.publish(
+                |feed| matches!(feed, Feed::L2Book(feed_coin) if feed_coin == coin),
+                json!({"channel": "l2Book", "data": data}),
+            );
+        }
+        let mids = self.all_mids();
+        self.publish(
+            |feed| matches!(feed, Feed::AllMids),
+            json!({"channel": "allMids", "data": {"mids": mids}}),
+        );
+    }
+}
+
+fn parse_amount(amount: &str) -> std::result::Result<f64, String> {
+    amount
+        .parse()
+        .ok()
+        .filter(|amount: &f64| *amount > 0.0)
+        .ok_or_else(|| format!("Invalid amount {amount}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        number::parse_number, BaseUrl, ClientCancelRequest, ClientLimit, ClientOrder,
+        ClientOrderRequest, ClientTwapRequest, ExchangeClient, ExchangeDataStatus,
+        ExchangeErrorKind, ExchangeResponseStatus, TwapStatus, UserData,
+    };
+    use ethers::signers::{LocalWallet, Signer};
+    use std::sync::Arc;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn paper_exchange() -> Result<PaperExchange> {
+        let meta = serde_json::from_value(json!({"universe": [{"name": "ETH", "szDecimals": 4}]}))
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let spot_meta = serde_json::from_value(json!({"universe": [], "tokens": []}))
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        Ok(PaperExchange::new(meta, spot_meta))
+    }
+
+    /// Book of ETH with levels as (price, size), best first
+    fn snapshot(
+        time: u64,
+        bids: &[(&str, &str)],
+        asks: &[(&str, &str)],
+    ) -> Result<L2SnapshotResponse> {
+        let side = |levels: &[(&str, &str)]| -> Vec<Value> {
+            levels
+                .iter()
+                .map(|(px, sz)| json!({"px": px, "sz": sz, "n": 1}))
+                .collect()
+        };
+        serde_json::from_value(json!({
+            "coin": "ETH",
+            "time": time,
+            "levels": [side(bids), side(asks)]
+        }))
+        .map_err(|e| Error::JsonParse(e.to_string()))
+    }
+
+    fn get_wallet() -> Result<LocalWallet> {
+        let priv_key = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e";
+        priv_key
+            .parse::<LocalWallet>()
+            .map_err(|e| Error::Wallet(e.to_string()))
+    }
+
+    fn statuses(response: ExchangeResponseStatus) -> Vec<ExchangeDataStatus> {
+        match response {
+            ExchangeResponseStatus::Ok(response) => {
+                response.data.map(|data| data.statuses).unwrap_or_default()
+            }
+            ExchangeResponseStatus::Err(e) => panic!("unexpected error {e}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_paper_trading_end_to_end() -> Result<()> {
+        let paper_exchange = Arc::new(paper_exchange()?);
+        paper_exchange.feed_l2_snapshot(&snapshot(
+            1000,
+            &[("1999", "2")],
+            &[("2001", "1"), ("2002", "5")],
+        )?)?;
+        let wallet = get_wallet()?;
+        let user = wallet.address();
+        paper_exchange.deposit(user, 1000.0);
+
+        let exchange_client = ExchangeClient::with_transport(
+            paper_exchange.clone(),
+            wallet,
+            Some(BaseUrl::Testnet),
+            None,
+            None,
+            None,
+        )
+        .await?;
+        let mut info_client =
+            InfoClient::with_transport(paper_exchange.clone(), Some(BaseUrl::Testnet)).await?;
+        let (sender, mut receiver) = unbounded_channel();
+        info_client
+            .subscribe(Subscription::UserEvents { user }, sender.clone())
+            .await?;
+        info_client
+            .subscribe(Subscription::OrderUpdates { user }, sender)
+            .await?;
+
+        // Takes the best ask and part of the next level
+        let response = exchange_client
+            .market_open("ETH", true, 1.5, None, None, None)
+            .await?;
+        let filled = match &statuses(response)[..] {
+            [ExchangeDataStatus::Filled(filled)] => filled.clone(),
+            statuses => panic!("unexpected statuses {statuses:?}"),
+        };
+        assert!((parse_number(&filled.total_sz)? - 1.5).abs() < EPSILON);
+        assert!((parse_number(&filled.avg_px)? - 6004.0 / 3.0).abs() < 1e-6);
+        assert!(
+            matches!(receiver.try_recv(), Ok(Message::OrderUpdates(updates)) if updates.data[0].status == "filled")
+        );
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(Message::User(user_events)) if matches!(&user_events.data, UserData::Fills(fills) if fills.len() == 2 && fills[0].crossed)
+        ));
+
+        let user_state = info_client.user_state(user).await?;
+        let position = &user_state.asset_positions[0].position;
+        assert!((parse_number(&position.szi)? - 1.5).abs() < EPSILON);
+
+        // Rests until books cross it, then fills at its price as a maker
+        let order = ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy: false,
+            reduce_only: true,
+            limit_px: 2010.0,
+            sz: 1.0,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+        };
+        let response = exchange_client.order(order, None, None).await?;
+        let oid = match &statuses(response)[..] {
+            [ExchangeDataStatus::Resting(resting)] => resting.oid,
+            statuses => panic!("unexpected statuses {statuses:?}"),
+        };
+        assert_eq!(info_client.open_orders(user).await?.len(), 1);
+        receiver.try_recv().ok();
+
+        paper_exchange.feed_l2_snapshot(&snapshot(2000, &[("2011", "0.4")], &[("2012", "1")])?)?;
+        paper_exchange.feed_l2_snapshot(&snapshot(3000, &[("2015", "3")], &[("2016", "1")])?)?;
+        let mut maker_fills = Vec::new();
+        while let Ok(message) = receiver.try_recv() {
+            if let Message::User(user_events) = message {
+                if let UserData::Fills(fills) = user_events.data {
+                    maker_fills.extend(fills);
+                }
+            }
+        }
+        assert_eq!(maker_fills.len(), 2);
+        assert!(maker_fills
+            .iter()
+            .all(|fill| fill.oid == oid && !fill.crossed && fill.dir == "Close Long"));
+        assert!(info_client.open_orders(user).await?.is_empty());
+        let fills = info_client.user_fills(user).await?;
+        assert_eq!(fills.len(), 4);
+        assert!((parse_number(&fills[0].px)? - 2010.0).abs() < EPSILON);
+
+        let user_state = info_client.user_state(user).await?;
+        let position = &user_state.asset_positions[0].position;
+        assert!((parse_number(&position.szi)? - 0.5).abs() < EPSILON);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_paper_exchange_rejections() -> Result<()> {
+        let paper_exchange = Arc::new(paper_exchange()?);
+        paper_exchange.feed_l2_snapshot(&snapshot(1000, &[("1999", "2")], &[("2001", "1")])?)?;
+        let wallet = get_wallet()?;
+        let user = wallet.address();
+        paper_exchange.deposit(user, 100.0);
+        let exchange_client = ExchangeClient::with_transport(
+            paper_exchange.clone(),
+            wallet,
+            Some(BaseUrl::Testnet),
+            None,
+            None,
+            None,
+        )
+        .await?;
+        let order = |is_buy, limit_px, sz, tif| ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy,
+            reduce_only: false,
+            limit_px,
+            sz,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif }),
+        };
+        let error_kind = |statuses: Vec<ExchangeDataStatus>| match &statuses[..] {
+            [ExchangeDataStatus::Error(e)] => e.kind,
+            statuses => panic!("unexpected statuses {statuses:?}"),
+        };
+
+        let response = exchange_client
+            .order(order(true, 1990.0, 0.1, Tif::Ioc), None, None)
+            .await?;
+        assert_eq!(
+            error_kind(statuses(response)),
+            ExchangeErrorKind::NoImmediateMatch
+        );
+        let response = exchange_client
+            .order(order(false, 1999.0, 0.1, Tif::Alo), None, None)
+            .await?;
+        assert_eq!(
+            error_kind(statuses(response)),
+            ExchangeErrorKind::PostOnlyWouldCross
+        );
+        // 1 ETH at 20x needs 100 USDC of margin
+        let response = exchange_client
+            .order(order(true, 2001.0, 1.1, Tif::Ioc), None, None)
+            .await?;
+        assert_eq!(
+            error_kind(statuses(response)),
+            ExchangeErrorKind::InsufficientMargin
+        );
+        exchange_client
+            .update_leverage(40, "ETH", true, None, None)
+            .await?;
+        let response = exchange_client
+            .order(order(true, 2001.0, 1.1, Tif::Ioc), None, None)
+            .await?;
+        assert!(matches!(
+            &statuses(response)[..],
+            [ExchangeDataStatus::Filled(_)]
+        ));
+
+        let cancel = ClientCancelRequest {
+            asset: "ETH".to_string(),
+            oid: 12345,
+        };
+        let response = exchange_client.cancel(cancel, None, None).await?;
+        assert_eq!(
+            error_kind(statuses(response)),
+            ExchangeErrorKind::OrderNotFound
+        );
+
+        // Twaps take what they can from the book at once
+        let twap = ClientTwapRequest {
+            asset: "ETH".to_string(),
+            is_buy: false,
+            reduce_only: true,
+            sz: 1.1,
+            minutes: 5,
+            randomize: false,
+        };
+        let response = exchange_client.twap_order(twap, None, None).await?;
+        let ExchangeResponseStatus::Ok(response) = response else {
+            panic!("expected ok response");
+        };
+        let TwapStatus::Running(running) = response.data.status else {
+            panic!("expected running twap");
+        };
+        let user_state = InfoClient::with_transport(paper_exchange.clone(), None)
+            .await?
+            .user_state(user)
+            .await?;
+        assert!(user_state.asset_positions.is_empty());
+        // Which leaves nothing to cancel
+        let response = exchange_client
+            .twap_cancel("ETH", running.twap_id, None, None)
+            .await?;
+        let ExchangeResponseStatus::Ok(response) = response else {
+            panic!("expected ok response");
+        };
+        assert!(matches!(response.data.status, TwapStatus::Error(_)));
+
+        let response = exchange_client
+            .usdc_transfer("1000", "0x0D1d9635D0640821d15e323ac8AdADfA9c111414", None)
+            .await?;
+        assert!(matches!(response, ExchangeResponseStatus::Err(_)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_paper_exchange_resting_reduce_only() -> Result<()> {
+        let paper_exchange = Arc::new(paper_exchange()?);
+        paper_exchange.feed_l2_snapshot(&snapshot(1000, &[("1999", "5")], &[("2001", "5")])?)?;
+        let wallet = get_wallet()?;
+        let user = wallet.address();
+        paper_exchange.deposit(user, 1000.0);
+        let exchange_client = ExchangeClient::with_transport(
+            paper_exchange.clone(),
+            wallet,
+            Some(BaseUrl::Testnet),
+            None,
+            None,
+            None,
+        )
+        .await?;
+        let info_client =
+            InfoClient::with_transport(paper_exchange.clone(), Some(BaseUrl::Testnet)).await?;
+        let reduce_only_sell = |limit_px| ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy: false,
+            reduce_only: true,
+            limit_px,
+            sz: 1.0,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+        };
+
+        exchange_client
+            .market_open("ETH", true, 1.0, None, None, None)
+            .await?;
+        exchange_client
+            .order(reduce_only_sell(2010.0), None, None)
+            .await?;
+        exchange_client
+            .order(reduce_only_sell(2020.0), None, None)
+            .await?;
+        exchange_client
+            .market_open("ETH", false, 0.6, None, None, None)
+            .await?;
+
+        // Fills what's left of the position, then the other order has nothing to reduce
+        paper_exchange.feed_l2_snapshot(&snapshot(2000, &[("2011", "3")], &[("2012", "3")])?)?;
+        let fills = info_client.user_fills(user).await?;
+        assert!((parse_number(&fills[0].sz)? - 0.4).abs() < EPSILON);
+        assert!(info_client
+            .user_state(user)
+            .await?
+            .asset_positions
+            .is_empty());
+        assert!(info_client.open_orders(user).await?.is_empty());
+
+        paper_exchange.feed_l2_snapshot(&snapshot(3000, &[("2021", "3")], &[("2022", "3")])?)?;
+        assert_eq!(info_client.user_fills(user).await?.len(), fills.len());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_paper_exchange_usd_send_by_agent() -> Result<()> {
+        let paper_exchange = Arc::new(paper_exchange()?);
+        let user = H160::from_low_u64_be(1);
+        let destination = H160::from_low_u64_be(2);
+        let agent = get_wallet()?;
+        paper_exchange.deposit(user, 1000.0);
+        paper_exchange.add_agent(user, agent.address());
+        let exchange_client = ExchangeClient::with_transport(
+            paper_exchange.clone(),
+            agent,
+            Some(BaseUrl::Testnet),
+            None,
+            None,
+            None,
+        )
+        .await?;
+        let info_client =
+            InfoClient::with_transport(paper_exchange.clone(), Some(BaseUrl::Testnet)).await?;
+
+        let response = exchange_client
+            .usdc_transfer("400", &format!("{destination:?}"), None)
+            .await?;
+        assert!(matches!(response, ExchangeResponseStatus::Ok(_)));
+        let withdrawable = |user| {
+            let info_client = &info_client;
+            async move { parse_number(&info_client.user_state(user).await?.withdrawable) }
+        };
+        assert!((withdrawable(user).await? - 600.0).abs() < EPSILON);
+        assert!((withdrawable(destination).await? - 400.0).abs() < EPSILON);
+        Ok(())
+    }
+}
@@ -1,76 +1,252 @@
-use crate::{prelude::*, BaseUrl, Error};
-use reqwest::{Client, Response};
-use serde::Deserialize;
-
-#[derive(Deserialize, Debug)]
-struct ErrorData {
-    data: String,
-    code: u16,
-    msg: String,
+use crate::{consts::REQUEST_WEIGHT_PER_MINUTE, prelude::*, transport::Transport, BaseUrl, Error};
+use lazy_static::lazy_static;
+use log::debug;
+use rand::{thread_rng, Rng};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// How failed requests are retried. Only rate limited (429), server (5xx) and connection
+/// errors are retried, and requests to `/exchange` only when sending them twice can't place
+/// or move funds twice.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// No retries, every request is sent once
+    pub fn none() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 0,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Exponential backoff before retry number `attempt` (starting at 0), with jitter so that
+    /// clients failing together don't retry together
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff);
+        backoff / 2 + backoff.mul_f64(thread_rng().gen_range(0.0..0.5))
+    }
+}
+
+/// Client side view of the exchange's request weight limit, which is shared by all requests
+/// from an IP address. Requests wait until the budget has refilled enough for their weight
+/// instead of being rejected with a 429.
+#[derive(Debug)]
+pub struct WeightBudget {
+    pub weight_per_minute: u32,
+    state: Mutex<(f64, Instant)>,
+}
+
+impl WeightBudget {
+    pub fn new(weight_per_minute: u32) -> WeightBudget {
+        WeightBudget {
+            weight_per_minute,
+            state: Mutex::new((weight_per_minute as f64, Instant::now())),
+        }
+    }
+
+    /// Takes `weight` from the budget and returns how long to wait before sending the request
+    fn reserve(&self, weight: u32) -> Duration {
+        let per_second = self.weight_per_minute as f64 / 60.0;
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let (available, updated_at) = &mut *state;
+
+        let now = Instant::now();
+        *available = (*available + now.duration_since(*updated_at).as_secs_f64() * per_second)
+            .min(self.weight_per_minute as f64);
+        *updated_at = now;
+        *available -= weight.min(self.weight_per_minute) as f64;
+
+        if *available >= 0.0 || per_second <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-*available / per_second)
+        }
+    }
+
+    pub async fn acquire(&self, weight: u32) {
+        let wait = self.reserve(weight);
+        if !wait.is_zero() {
+            debug!("Waiting {wait:?} for request weight budget");
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+lazy_static! {
+    static ref SHARED_WEIGHT_BUDGET: Arc<WeightBudget> =
+        Arc::new(WeightBudget::new(REQUEST_WEIGHT_PER_MINUTE));
 }
 
 pub struct HttpClient {
-    pub client: Client,
-    pub base_url: String,
+    pub transport: Arc<dyn Transport>,
+    pub base_url: BaseUrl,
+    pub retry_policy: RetryPolicy,
+    /// Shared process-wide by default, as the exchange limits all requests from an IP together
+    pub weight_budget: Arc<WeightBudget>,
 }
 
-async fn parse_response(response: Response) -> Result<String> {
-    let status_code = response.status().as_u16();
-    let headers = response.headers().clone();
-    let text = response
-        .text()
-        .await
-        .map_err(|e| Error::GenericRequest(e.to_string()))?;
+fn is_transient(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::ClientRequest {
+            status_code: 429,
+            ..
+        } | Error::ServerRequest { .. }
+            | Error::Connection(_)
+    )
+}
 
-    if status_code < 400 {
-        return Ok(text);
+impl HttpClient {
+    pub fn new(transport: Arc<dyn Transport>, base_url: BaseUrl) -> HttpClient {
+        HttpClient {
+            transport,
+            base_url,
+            retry_policy: RetryPolicy::default(),
+            weight_budget: SHARED_WEIGHT_BUDGET.clone(),
+        }
     }
-    let error_data = serde_json::from_str::<ErrorData>(&text);
-    if (400..500).contains(&status_code) {
-        let client_error = match error_data {
-            Ok(error_data) => Error::ClientRequest {
-                status_code,
-                error_code: Some(error_data.code),
-                error_message: error_data.msg,
-                headers,
-                error_data: Some(error_data.data),
-            },
-            Err(err) => Error::ClientRequest {
-                status_code,
-                error_message: text,
-                headers,
-                error_code: None,
-                error_data: Some(err.to_string()),
-            },
+
+    /// Sends `data` once the weight budget allows it. `retryable` requests are sent again
+    /// according to the retry policy when they fail with a transient error.
+    pub async fn post(
+        &self,
+        url_path: &'static str,
+        data: String,
+        weight: u32,
+        retryable: bool,
+    ) -> Result<String> {
+        let max_retries = if retryable {
+            self.retry_policy.max_retries
+        } else {
+            0
         };
-        return Err(client_error);
+
+        let mut attempt = 0;
+        loop {
+            self.weight_budget.acquire(weight).await;
+            match self.transport.post(url_path, data.clone()).await {
+                Err(e) if is_transient(&e) && attempt < max_retries => {
+                    let backoff = self.retry_policy.backoff(attempt);
+                    debug!("Retrying {url_path} in {backoff:?} after error: {e}");
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
     }
 
-    Err(Error::ServerRequest {
-        status_code,
-        error_message: text,
-    })
+    pub fn is_mainnet(&self) -> bool {
+        self.base_url.is_mainnet()
+    }
 }
 
-impl HttpClient {
-    pub async fn post(&self, url_path: &'static str, data: String) -> Result<String> {
-        let full_url = format!("{}{url_path}", self.base_url);
-        let request = self
-            .client
-            .post(full_url)
-            .header("Content-Type", "application/json")
-            .body(data)
-            .build()
-            .map_err(|e| Error::GenericRequest(e.to_string()))?;
-        let result = self
-            .client
-            .execute(request)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockTransport;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_backoff_is_exponential_and_capped() {
+        let retry_policy = RetryPolicy {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(1000),
+        };
+        for (attempt, full_backoff) in [(0, 100), (1, 200), (2, 400), (3, 800), (4, 1000)] {
+            let backoff = retry_policy.backoff(attempt);
+            let full_backoff = Duration::from_millis(full_backoff);
+            assert!(backoff >= full_backoff / 2 && backoff <= full_backoff);
+        }
+    }
+
+    #[test]
+    fn test_weight_budget_waits_once_spent() {
+        let weight_budget = WeightBudget::new(60);
+        assert_eq!(weight_budget.reserve(20), Duration::ZERO);
+        assert_eq!(weight_budget.reserve(40), Duration::ZERO);
+
+        // Refills one weight per second
+        let wait = weight_budget.reserve(2);
+        assert!(wait > Duration::from_millis(1900) && wait <= Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_retries_only_retryable_requests() -> Result<()> {
+        // Fails with a server error every other request
+        let count = Arc::new(AtomicU32::new(0));
+        let transport_count = count.clone();
+        let transport = MockTransport::new(move |_, _| {
+            match transport_count.fetch_add(1, Ordering::SeqCst) % 2 {
+                0 => Err(Error::ServerRequest {
+                    status_code: 502,
+                    error_message: "Bad Gateway".to_string(),
+                }),
+                _ => Ok(serde_json::json!({})),
+            }
+        });
+        let http_client = HttpClient {
+            retry_policy: RetryPolicy {
+                max_retries: 1,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+            },
+            ..HttpClient::new(Arc::new(transport), BaseUrl::Testnet)
+        };
+
+        assert_eq!(
+            http_client.post("/info", "{}".to_string(), 1, true).await?,
+            "{}"
+        );
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+        assert!(http_client
+            .post("/exchange", "{}".to_string(), 1, false)
             .await
-            .map_err(|e| Error::GenericRequest(e.to_string()))?;
-        parse_response(result).await
+            .is_err());
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+        Ok(())
     }
 
-    pub fn is_mainnet(&self) -> bool {
-        self.base_url == BaseUrl::Mainnet.get_url()
+    #[test]
+    fn test_transient_errors() {
+        assert!(is_transient(&Error::ServerRequest {
+            status_code: 502,
+            error_message: String::new(),
+        }));
+        assert!(is_transient(&Error::ClientRequest {
+            status_code: 429,
+            error_code: None,
+            error_message: String::new(),
+            headers: Default::default(),
+            error_data: None,
+        }));
+        assert!(!is_transient(&Error::ClientRequest {
+            status_code: 422,
+            error_code: None,
+            error_message: String::new(),
+            headers: Default::default(),
+            error_data: None,
+        }));
     }
 }
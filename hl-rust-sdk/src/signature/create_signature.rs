@@ -1,54 +1,47 @@
-use ethers::{
-    core::k256::{elliptic_curve::FieldBytes, Secp256k1},
-    signers::LocalWallet,
-    types::{transaction::eip712::Eip712, Signature, H256, U256},
-};
+use ethers::types::{transaction::eip712::Eip712, Signature, H256};
 
-use crate::{prelude::*, proxy_digest::Sha256Proxy, signature::agent::l1, Error};
+use crate::{prelude::*, signature::agent::l1, Error, HyperliquidSigner};
 
-pub(crate) fn sign_l1_action(
-    wallet: &LocalWallet,
+/// Typed data signed for an L1 action with `connection_id`
+pub(crate) fn l1_agent(connection_id: H256, is_mainnet: bool) -> l1::Agent {
+    let source = if is_mainnet { "a" } else { "b" }.to_string();
+    l1::Agent {
+        source,
+        connection_id,
+    }
+}
+
+pub(crate) async fn sign_l1_action<S: HyperliquidSigner + ?Sized>(
+    signer: &S,
     connection_id: H256,
     is_mainnet: bool,
 ) -> Result<Signature> {
-    let source = if is_mainnet { "a" } else { "b" }.to_string();
-    sign_typed_data(
-        &l1::Agent {
-            source,
-            connection_id,
-        },
-        wallet,
-    )
+    sign_typed_data(&l1_agent(connection_id, is_mainnet), signer).await
 }
 
-pub(crate) fn sign_typed_data<T: Eip712>(payload: &T, wallet: &LocalWallet) -> Result<Signature> {
+pub(crate) fn typed_data_hash<T: Eip712>(payload: &T) -> Result<H256> {
     let encoded = payload
         .encode_eip712()
         .map_err(|e| Error::Eip712(e.to_string()))?;
-
-    sign_hash(H256::from(encoded), wallet)
+    Ok(H256::from(encoded))
 }
 
-fn sign_hash(hash: H256, wallet: &LocalWallet) -> Result<Signature> {
-    let (sig, rec_id) = wallet
-        .signer()
-        .sign_digest_recoverable(Sha256Proxy::from(hash))
-        .map_err(|e| Error::SignatureFailure(e.to_string()))?;
-
-    let v = u8::from(rec_id) as u64 + 27;
-
-    let r_bytes: FieldBytes<Secp256k1> = sig.r().into();
-    let s_bytes: FieldBytes<Secp256k1> = sig.s().into();
-    let r = U256::from_big_endian(r_bytes.as_slice());
-    let s = U256::from_big_endian(s_bytes.as_slice());
-
-    Ok(Signature { r, s, v })
+pub(crate) async fn sign_typed_data<T: Eip712, S: HyperliquidSigner + ?Sized>(
+    payload: &T,
+    signer: &S,
+) -> Result<Signature> {
+    signer.sign_hash(typed_data_hash(payload)?).await
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{UsdSend, Withdraw3};
+    use crate::{
+        ApproveAgent, ApproveBuilderFee, SpotSend, UsdSend, Withdraw3, ARBITRUM_ONE_CHAIN_ID,
+        ARBITRUM_SEPOLIA_CHAIN_ID,
+    };
+    use ethers::signers::LocalWallet;
+    use ethers::types::H160;
     use std::str::FromStr;
 
     fn get_wallet() -> Result<LocalWallet> {
@@ -58,8 +51,8 @@ mod tests {
             .map_err(|e| Error::Wallet(e.to_string()))
     }
 
-    #[test]
-    fn test_sign_l1_action() -> Result<()> {
+    #[tokio::test]
+    async fn test_sign_l1_action() -> Result<()> {
         let wallet = get_wallet()?;
         let connection_id =
             H256::from_str("0xde6c4037798a4434ca03cd05f00e3b803126221375cd1e7eaaaf041768be06eb")
@@ -67,23 +60,62 @@ mod tests {
 
         let expected_mainnet_sig = "fa8a41f6a3fa728206df80801a83bcbfbab08649cd34d9c0bfba7c7b2f99340f53a00226604567b98a1492803190d65a201d6805e5831b7044f17fd530aec7841c";
         assert_eq!(
-            sign_l1_action(&wallet, connection_id, true)?.to_string(),
+            sign_l1_action(&wallet, connection_id, true)
+                .await?
+                .to_string(),
             expected_mainnet_sig
         );
         let expected_testnet_sig = "1713c0fc661b792a50e8ffdd59b637b1ed172d9a3aa4d801d9d88646710fb74b33959f4d075a7ccbec9f2374a6da21ffa4448d58d0413a0d335775f680a881431c";
         assert_eq!(
-            sign_l1_action(&wallet, connection_id, false)?.to_string(),
+            sign_l1_action(&wallet, connection_id, false)
+                .await?
+                .to_string(),
             expected_testnet_sig
         );
         Ok(())
     }
 
-    #[test]
-    fn test_sign_usd_transfer_action() -> Result<()> {
+    struct RemoteSigner {
+        wallet: LocalWallet,
+    }
+
+    #[async_trait::async_trait]
+    impl HyperliquidSigner for RemoteSigner {
+        fn address(&self) -> ethers::types::H160 {
+            HyperliquidSigner::address(&self.wallet)
+        }
+
+        async fn sign_hash(&self, hash: H256) -> Result<Signature> {
+            tokio::task::yield_now().await;
+            HyperliquidSigner::sign_hash(&self.wallet, hash).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sign_l1_action_with_custom_signer() -> Result<()> {
+        let signer = RemoteSigner {
+            wallet: get_wallet()?,
+        };
+        let connection_id =
+            H256::from_str("0xde6c4037798a4434ca03cd05f00e3b803126221375cd1e7eaaaf041768be06eb")
+                .map_err(|e| Error::GenericParse(e.to_string()))?;
+
+        let expected_mainnet_sig = "fa8a41f6a3fa728206df80801a83bcbfbab08649cd34d9c0bfba7c7b2f99340f53a00226604567b98a1492803190d65a201d6805e5831b7044f17fd530aec7841c";
+        assert_eq!(
+            sign_l1_action(&signer, connection_id, true)
+                .await?
+                .to_string(),
+            expected_mainnet_sig
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign_usd_transfer_action() -> Result<()> {
         let wallet = get_wallet()?;
 
         let usd_send = UsdSend {
-            signature_chain_id: 421614.into(),
+            signature_chain_id: ARBITRUM_SEPOLIA_CHAIN_ID.into(),
             hyperliquid_chain: "Testnet".to_string(),
             destination: "0x0D1d9635D0640821d15e323ac8AdADfA9c111414".to_string(),
             amount: "1".to_string(),
@@ -92,18 +124,25 @@ mod tests {
 
         let expected_sig = "214d507bbdaebba52fa60928f904a8b2df73673e3baba6133d66fe846c7ef70451e82453a6d8db124e7ed6e60fa00d4b7c46e4d96cb2bd61fd81b6e8953cc9d21b";
         assert_eq!(
-            sign_typed_data(&usd_send, &wallet)?.to_string(),
+            sign_typed_data(&usd_send, &wallet).await?.to_string(),
             expected_sig
         );
+
+        let usd_send = UsdSend {
+            signature_chain_id: ARBITRUM_ONE_CHAIN_ID.into(),
+            hyperliquid_chain: "Mainnet".to_string(),
+            ..usd_send
+        };
+        assert_eq!(sign_typed_data(&usd_send, &wallet).await?.to_string(), "00029416db37168b79c4fffe1c3df178dafefeae9dbb271036dc79d6ca09a6dc1398dc9e056762eaf9989248aaeb2f7569c50d03f1acc29a0360b3349b5a4c631c");
         Ok(())
     }
 
-    #[test]
-    fn test_sign_withdraw_from_bridge_action() -> Result<()> {
+    #[tokio::test]
+    async fn test_sign_withdraw_from_bridge_action() -> Result<()> {
         let wallet = get_wallet()?;
 
-        let usd_send = Withdraw3 {
-            signature_chain_id: 421614.into(),
+        let withdraw = Withdraw3 {
+            signature_chain_id: ARBITRUM_SEPOLIA_CHAIN_ID.into(),
             hyperliquid_chain: "Testnet".to_string(),
             destination: "0x0D1d9635D0640821d15e323ac8AdADfA9c111414".to_string(),
             amount: "1".to_string(),
@@ -112,9 +151,110 @@ mod tests {
 
         let expected_sig = "b3172e33d2262dac2b4cb135ce3c167fda55dafa6c62213564ab728b9f9ba76b769a938e9f6d603dae7154c83bf5a4c3ebab81779dc2db25463a3ed663c82ae41c";
         assert_eq!(
-            sign_typed_data(&usd_send, &wallet)?.to_string(),
+            sign_typed_data(&withdraw, &wallet).await?.to_string(),
+            expected_sig
+        );
+
+        let withdraw = Withdraw3 {
+            signature_chain_id: ARBITRUM_ONE_CHAIN_ID.into(),
+            hyperliquid_chain: "Mainnet".to_string(),
+            ..withdraw
+        };
+        assert_eq!(sign_typed_data(&withdraw, &wallet).await?.to_string(), "0a8078ba0a3bde684c2b21a5bd1a69e550f6c1e633696971615546b5d969cae03fcecf847a83aca4549cfb1a2e0e7c85b2b706e3470bb03ad858c23f6d1d25a81c");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign_spot_transfer_action() -> Result<()> {
+        let wallet = get_wallet()?;
+
+        let spot_send = SpotSend {
+            signature_chain_id: ARBITRUM_SEPOLIA_CHAIN_ID.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            destination: "0x0D1d9635D0640821d15e323ac8AdADfA9c111414".to_string(),
+            token: "PURR:0xc1fb593aeffbeb02f85e0308e9956a90".to_string(),
+            amount: "1".to_string(),
+            time: 1690393044548,
+        };
+
+        let expected_sig = "685fd38583ead5c6202cc0e9fafcbb27b75f8b0f8669b211c137ce69c5f55e92349fc42739d6c61b940c45a9a226d9cb10d52a8f3bb00e5a3680c4a5fefc31731b";
+        assert_eq!(
+            sign_typed_data(&spot_send, &wallet).await?.to_string(),
+            expected_sig
+        );
+
+        let spot_send = SpotSend {
+            signature_chain_id: ARBITRUM_ONE_CHAIN_ID.into(),
+            hyperliquid_chain: "Mainnet".to_string(),
+            ..spot_send
+        };
+        assert_eq!(sign_typed_data(&spot_send, &wallet).await?.to_string(), "4ff6adc3ad1fd942c0a3491c223244f04196946e79e0a8618376b98d0690ab4366c8e479d3e8e3c895825dcdc54abc6b8040464594ed20f5eeff055fbcbf7a8f1b");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign_approve_named_agent_action() -> Result<()> {
+        let wallet = get_wallet()?;
+
+        let approve_agent = ApproveAgent {
+            signature_chain_id: ARBITRUM_SEPOLIA_CHAIN_ID.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            agent_address: H160::from_str("0x0D1d9635D0640821d15e323ac8AdADfA9c111414")
+                .map_err(|e| Error::GenericParse(e.to_string()))?,
+            agent_name: Some("bot".to_string()),
+            nonce: 1690393044548,
+        };
+
+        let expected_sig = "9592d86df152f629f552079da5002114e9cef0a3bcb3d1aba4dcd616be69c17f625b3a8a4066f89e38fa661e821a638d1e48082bea2cec18fc5b9f45b30cb1521c";
+        assert_eq!(
+            sign_typed_data(&approve_agent, &wallet).await?.to_string(),
             expected_sig
         );
+
+        let approve_agent = ApproveAgent {
+            signature_chain_id: ARBITRUM_ONE_CHAIN_ID.into(),
+            hyperliquid_chain: "Mainnet".to_string(),
+            ..approve_agent
+        };
+        assert_eq!(
+            sign_typed_data(&approve_agent, &wallet).await?.to_string(),
+            "55aeaf0d7280fffdc4adc4a57c4bc21a1c37beaa3110c5fb397b44a7dcdbc7a47f9f6b70436f6c348573da91bd88886d0c684a31ec50510588d25faebb9cbc191b"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign_approve_builder_fee_action() -> Result<()> {
+        let wallet = get_wallet()?;
+
+        let approve_builder_fee = ApproveBuilderFee {
+            signature_chain_id: ARBITRUM_SEPOLIA_CHAIN_ID.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            max_fee_rate: "0.01%".to_string(),
+            builder: H160::from_str("0x0D1d9635D0640821d15e323ac8AdADfA9c111414")
+                .map_err(|e| Error::GenericParse(e.to_string()))?,
+            nonce: 1690393044548,
+        };
+
+        let expected_sig = "e8a5e50851c53f2f9fe70fe58eb908fc7435aac120d89b11eaa4ad72f11ab409345f3a5acd1f3c1aa65c3780459a591cac1622f4aa97ce32ae07faf89bf9c1d11c";
+        assert_eq!(
+            sign_typed_data(&approve_builder_fee, &wallet)
+                .await?
+                .to_string(),
+            expected_sig
+        );
+
+        let approve_builder_fee = ApproveBuilderFee {
+            signature_chain_id: ARBITRUM_ONE_CHAIN_ID.into(),
+            hyperliquid_chain: "Mainnet".to_string(),
+            ..approve_builder_fee
+        };
+        assert_eq!(
+            sign_typed_data(&approve_builder_fee, &wallet)
+                .await?
+                .to_string(),
+            "4fd3471e580a0d27dbe05a57a96f650c691b98c8eafad7556388baff34df3d757879bb3013c8db9e806a5ba35e87cafe611c1c881413095591a5de6e6edb6be81b"
+        );
         Ok(())
     }
 }
@@ -1,4 +1,8 @@
 pub(crate) mod agent;
 mod create_signature;
+mod signer;
+mod verify;
 
 pub(crate) use create_signature::{sign_l1_action, sign_typed_data};
+pub use signer::HyperliquidSigner;
+pub use verify::{recover_signer, signing_hash, verify_signer};
@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+use ethers::{
+    core::k256::{elliptic_curve::FieldBytes, Secp256k1},
+    signers::{LocalWallet, Signer},
+    types::{Signature, H160, H256, U256},
+};
+
+use crate::{prelude::*, proxy_digest::Sha256Proxy, Error};
+
+/// Signs the EIP-712 digests of exchange actions. Implement it to sign with a remote service,
+/// a KMS or an HSM instead of a key held in memory.
+#[async_trait]
+pub trait HyperliquidSigner: Send + Sync {
+    /// Address the exchange will recover from this signer's signatures
+    fn address(&self) -> H160;
+
+    /// Signs a 32 byte digest, returning a recoverable signature with `v` set to 27 or 28
+    async fn sign_hash(&self, hash: H256) -> Result<Signature>;
+}
+
+#[async_trait]
+impl HyperliquidSigner for LocalWallet {
+    fn address(&self) -> H160 {
+        Signer::address(self)
+    }
+
+    async fn sign_hash(&self, hash: H256) -> Result<Signature> {
+        let (sig, rec_id) = self
+            .signer()
+            .sign_digest_recoverable(Sha256Proxy::from(hash))
+            .map_err(|e| Error::SignatureFailure(e.to_string()))?;
+
+        let v = u8::from(rec_id) as u64 + 27;
+
+        let r_bytes: FieldBytes<Secp256k1> = sig.r().into();
+        let s_bytes: FieldBytes<Secp256k1> = sig.s().into();
+        let r = U256::from_big_endian(r_bytes.as_slice());
+        let s = U256::from_big_endian(s_bytes.as_slice());
+
+        Ok(Signature { r, s, v })
+    }
+}
@@ -0,0 +1,127 @@
+use ethers::types::{H160, H256};
+
+use crate::{
+    prelude::*,
+    signature::create_signature::{l1_agent, typed_data_hash},
+    Actions, Error, ExchangePayload,
+};
+
+/// EIP-712 digest that was signed for `payload`. User signed actions carry their own chain,
+/// L1 actions are hashed with their nonce, vault and expiry into the connection id of an agent
+/// for `is_mainnet`.
+pub fn signing_hash(payload: &ExchangePayload, is_mainnet: bool) -> Result<H256> {
+    let action: Actions = serde_json::from_value(payload.action.clone())
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+    match &action {
+        Actions::UsdSend(usd_send) => typed_data_hash(usd_send),
+        Actions::Withdraw3(withdraw) => typed_data_hash(withdraw),
+        Actions::SpotSend(spot_send) => typed_data_hash(spot_send),
+        Actions::ApproveAgent(approve_agent) => typed_data_hash(approve_agent),
+        Actions::ApproveBuilderFee(approve_builder_fee) => typed_data_hash(approve_builder_fee),
+        _ => {
+            let connection_id =
+                action.hash(payload.nonce, payload.vault_address, payload.expires_after)?;
+            typed_data_hash(&l1_agent(connection_id, is_mainnet))
+        }
+    }
+}
+
+/// Address that signed `payload`, which the exchange checks against the user, vault or their
+/// approved agents
+pub fn recover_signer(payload: &ExchangePayload, is_mainnet: bool) -> Result<H160> {
+    let hash = signing_hash(payload, is_mainnet)?;
+    payload
+        .signature
+        .recover(hash)
+        .map_err(|e| Error::SignatureFailure(e.to_string()))
+}
+
+/// Fails with `Error::SignerMismatch` unless `payload` was signed by `signer`
+pub fn verify_signer(payload: &ExchangePayload, is_mainnet: bool, signer: H160) -> Result<()> {
+    let recovered = recover_signer(payload, is_mainnet)?;
+    if recovered != signer {
+        return Err(Error::SignerMismatch {
+            expected: signer,
+            recovered,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExchangePayloadBuilder, HyperliquidSigner, UsdSend, ARBITRUM_SEPOLIA_CHAIN_ID};
+    use ethers::signers::LocalWallet;
+    use serde_json::json;
+
+    fn get_wallet() -> Result<LocalWallet> {
+        let priv_key = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e";
+        priv_key
+            .parse::<LocalWallet>()
+            .map_err(|e| Error::Wallet(e.to_string()))
+    }
+
+    /// Payload as received by a proxy or read from a log
+    fn round_trip(payload: &ExchangePayload) -> Result<ExchangePayload> {
+        let body = serde_json::to_string(payload).map_err(|e| Error::JsonParse(e.to_string()))?;
+        serde_json::from_str(&body).map_err(|e| Error::JsonParse(e.to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_recover_l1_action_signer() -> Result<()> {
+        let wallet = get_wallet()?;
+        let action = serde_json::from_value(json!({
+            "type": "order",
+            "orders": [{
+                "a": 1, "b": true, "p": "2000.0", "s": "3.5", "r": false,
+                "t": {"limit": {"tif": "Ioc"}},
+                "c": "0x1e60610f0b3d420597c08f56a5225bd1"
+            }],
+            "grouping": "na"
+        }))
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let payload = ExchangePayloadBuilder::new(&wallet, false)
+            .nonce(1583838)
+            .vault_address(Some(H160::from_low_u64_be(1)))
+            .expires_after(Some(1593838))
+            .build(action)
+            .await?;
+        let payload = round_trip(&payload)?;
+
+        assert_eq!(recover_signer(&payload, false)?, wallet.address());
+        verify_signer(&payload, false, wallet.address())?;
+        // Signed for testnet, so mainnet recovers some other address
+        assert!(matches!(
+            verify_signer(&payload, true, wallet.address()),
+            Err(Error::SignerMismatch { .. })
+        ));
+
+        let tampered = ExchangePayload {
+            nonce: 1583839,
+            ..payload
+        };
+        assert_ne!(recover_signer(&tampered, false)?, wallet.address());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_recover_user_signed_action_signer() -> Result<()> {
+        let wallet = get_wallet()?;
+        let payload = ExchangePayloadBuilder::new(&wallet, false)
+            .build(Actions::UsdSend(UsdSend {
+                signature_chain_id: ARBITRUM_SEPOLIA_CHAIN_ID.into(),
+                hyperliquid_chain: "Testnet".to_string(),
+                destination: "0x0D1d9635D0640821d15e323ac8AdADfA9c111414".to_string(),
+                amount: "1".to_string(),
+                time: 1690393044548,
+            }))
+            .await?;
+        let payload = round_trip(&payload)?;
+
+        // The network of user signed actions is part of the action
+        assert_eq!(recover_signer(&payload, false)?, wallet.address());
+        assert_eq!(recover_signer(&payload, true)?, wallet.address());
+        Ok(())
+    }
+}
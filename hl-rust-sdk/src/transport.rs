@@ -0,0 +1,253 @@
+use async_trait::async_trait;
+use reqwest::{Client, Response};
+use serde::Deserialize;
+use std::sync::Mutex;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{prelude::*, Error, Message, Subscription};
+
+/// Sends request bodies to the `/info` and `/exchange` endpoints and returns the response
+/// bodies. Implementations make a single attempt; retries and the weight budget are handled by
+/// the `HttpClient` on top.
+#[async_trait]
+pub trait Transport: Send + Sync {
+    async fn post(&self, url_path: &str, body: String) -> Result<String>;
+
+    /// Serves `subscription` from the transport itself, as the `PaperExchange` does, rather
+    /// than from the websocket. `None` if the transport doesn't serve subscriptions.
+    fn subscribe(
+        &self,
+        _subscription: &Subscription,
+        _sender_channel: UnboundedSender<Message>,
+    ) -> Option<Result<u32>> {
+        None
+    }
+
+    /// Removes a subscription made with `Transport::subscribe`
+    fn unsubscribe(&self, _subscription_id: u32) -> Option<Result<()>> {
+        None
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct ErrorData {
+    data: String,
+    code: u16,
+    msg: String,
+}
+
+async fn parse_response(response: Response) -> Result<String> {
+    let status_code = response.status().as_u16();
+    let headers = Box::new(response.headers().clone());
+    let text = response
+        .text()
+        .await
+        .map_err(|e| Error::GenericRequest(e.to_string()))?;
+
+    if status_code < 400 {
+        return Ok(text);
+    }
+    let error_data = serde_json::from_str::<ErrorData>(&text);
+    if (400..500).contains(&status_code) {
+        let client_error = match error_data {
+            Ok(error_data) => Error::ClientRequest {
+                status_code,
+                error_code: Some(error_data.code),
+                error_message: error_data.msg,
+                headers,
+                error_data: Some(error_data.data),
+            },
+            Err(err) => Error::ClientRequest {
+                status_code,
+                error_message: text,
+                headers,
+                error_code: None,
+                error_data: Some(err.to_string()),
+            },
+        };
+        return Err(client_error);
+    }
+
+    Err(Error::ServerRequest {
+        status_code,
+        error_message: text,
+    })
+}
+
+/// Transport to the Hyperliquid API over HTTP
+pub struct ReqwestTransport {
+    pub client: Client,
+    pub base_url: String,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: Client, base_url: String) -> ReqwestTransport {
+        ReqwestTransport { client, base_url }
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn post(&self, url_path: &str, body: String) -> Result<String> {
+        let full_url = format!("{}{url_path}", self.base_url);
+        let request = self
+            .client
+            .post(full_url)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .build()
+            .map_err(|e| Error::GenericRequest(e.to_string()))?;
+        let response = self.client.execute(request).await.map_err(|e| {
+            if e.is_connect() || e.is_timeout() {
+                Error::Connection(e.to_string())
+            } else {
+                Error::GenericRequest(e.to_string())
+            }
+        })?;
+        parse_response(response).await
+    }
+}
+
+type MockHandler = dyn Fn(&str, &serde_json::Value) -> Result<serde_json::Value> + Send + Sync;
+
+/// In-process stand-in for the API, answering each request with the JSON returned by
+/// `handler` for its url path and body. Every request is recorded so tests can check what a
+/// client sent.
+pub struct MockTransport {
+    handler: Box<MockHandler>,
+    requests: Mutex<Vec<(String, serde_json::Value)>>,
+}
+
+impl MockTransport {
+    pub fn new(
+        handler: impl Fn(&str, &serde_json::Value) -> Result<serde_json::Value> + Send + Sync + 'static,
+    ) -> MockTransport {
+        MockTransport {
+            handler: Box::new(handler),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Url paths and bodies of the requests received so far, oldest first
+    pub fn requests(&self) -> Vec<(String, serde_json::Value)> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn post(&self, url_path: &str, body: String) -> Result<String> {
+        let body: serde_json::Value =
+            serde_json::from_str(&body).map_err(|e| Error::JsonParse(e.to_string()))?;
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((url_path.to_string(), body.clone()));
+        let response = (self.handler)(url_path, &body)?;
+        serde_json::to_string(&response).map_err(|e| Error::JsonParse(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{
+        BaseUrl, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient, ExchangeDataStatus,
+        ExchangeResponseStatus, InfoClient, Tif,
+    };
+    use ethers::signers::LocalWallet;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    pub(crate) fn mock_api() -> Arc<MockTransport> {
+        Arc::new(MockTransport::new(|url_path, body| {
+            match (url_path, body["type"].as_str()) {
+                ("/info", Some("allMids")) => Ok(json!({"BTC": "50000.5"})),
+                ("/info", Some("meta")) => {
+                    Ok(json!({"universe": [{"name": "ETH", "szDecimals": 4}]}))
+                }
+                ("/info", Some("spotMeta")) => Ok(json!({"universe": [], "tokens": []})),
+                ("/exchange", _) => Ok(json!({
+                    "status": "ok",
+                    "response": {"type": "order", "data": {"statuses": [{"resting": {"oid": 7}}]}}
+                })),
+                _ => Err(Error::GenericRequest(format!("unexpected request {body}"))),
+            }
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_info_client_with_mock_transport() -> Result<()> {
+        let transport = mock_api();
+        let info_client = InfoClient::with_transport(transport.clone(), None).await?;
+
+        let all_mids = info_client.all_mids().await?;
+        assert_eq!(all_mids["BTC"], "50000.5");
+        assert_eq!(
+            transport.requests(),
+            vec![("/info".to_string(), json!({"type": "allMids"}))]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mock_transport_records_failed_requests() {
+        let transport = MockTransport::new(|_, _| Err(Error::GenericRequest("down".to_string())));
+
+        let res = transport
+            .post("/info", r#"{"type":"meta"}"#.to_string())
+            .await;
+        assert!(matches!(res, Err(Error::GenericRequest(_))));
+        assert_eq!(
+            transport.requests(),
+            vec![("/info".to_string(), json!({"type": "meta"}))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_exchange_client_with_mock_transport() -> Result<()> {
+        let transport = mock_api();
+        let wallet: LocalWallet =
+            "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+                .parse()
+                .map_err(|e: ethers::signers::WalletError| Error::Wallet(e.to_string()))?;
+        let exchange_client = ExchangeClient::with_transport(
+            transport.clone(),
+            wallet,
+            Some(BaseUrl::Testnet),
+            None,
+            None,
+            None,
+        )
+        .await?;
+
+        let order = ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px: 1800.0,
+            sz: 0.01,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: Tif::Gtc }),
+        };
+        let response = exchange_client.order(order, None, None).await?;
+        let ExchangeResponseStatus::Ok(response) = response else {
+            panic!("expected ok response");
+        };
+        let statuses = response.data.map(|data| data.statuses).unwrap_or_default();
+        assert!(
+            matches!(&statuses[..], [ExchangeDataStatus::Resting(resting)] if resting.oid == 7)
+        );
+
+        let (url_path, body) = transport.requests().pop().unwrap_or_default();
+        assert_eq!(url_path, "/exchange");
+        assert_eq!(
+            body["action"]["orders"],
+            json!([{"a": 0, "b": true, "p": "1800", "s": "0.01", "r": false, "t": {"limit": {"tif": "Gtc"}}}])
+        );
+        Ok(())
+    }
+}
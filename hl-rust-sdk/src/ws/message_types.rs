@@ -26,6 +26,11 @@ pub struct UserFills {
     pub data: UserFillsData,
 }
 
+#[derive(Deserialize, Clone, Debug)]
+pub struct UserTwapSliceFills {
+    pub data: UserTwapSliceFillsData,
+}
+
 #[derive(Deserialize, Clone, Debug)]
 pub struct Candle {
     pub data: CandleData,
@@ -51,6 +51,30 @@ pub struct TradeInfo {
     pub tid: u64,
 }
 
+#[cfg(feature = "rust_decimal")]
+crate::number::decimal_accessors!(TradeInfo {
+    px_decimal: px,
+    sz_decimal: sz,
+    start_position_decimal: start_position,
+    closed_pnl_decimal: closed_pnl,
+    fee_decimal: fee,
+});
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapSliceFill {
+    pub fill: TradeInfo,
+    pub twap_id: u64,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UserTwapSliceFillsData {
+    pub user: H160,
+    pub twap_slice_fills: Vec<TwapSliceFill>,
+    pub is_snapshot: Option<bool>,
+}
+
 #[derive(Deserialize, Clone, Debug)]
 pub struct UserFillsData {
     pub user: H160,
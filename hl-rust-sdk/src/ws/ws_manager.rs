@@ -1,7 +1,7 @@
 use crate::{
     prelude::*,
     ws::message_types::{AllMids, Candle, L2Book, OrderUpdates, Trades, User},
-    Error, Notification, UserFills, UserFundings, UserNonFundingLedgerUpdates,
+    Error, Notification, UserFills, UserFundings, UserNonFundingLedgerUpdates, UserTwapSliceFills,
 };
 use futures_util::{stream::SplitSink, SinkExt, StreamExt};
 use log::error;
@@ -47,6 +47,7 @@ pub enum Subscription {
     UserFundings { user: H160 },
     UserNonFundingLedgerUpdates { user: H160 },
     Notification { user: H160 },
+    UserTwapSliceFills { user: H160 },
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -64,6 +65,7 @@ pub enum Message {
     UserFundings(UserFundings),
     UserNonFundingLedgerUpdates(UserNonFundingLedgerUpdates),
     Notification(Notification),
+    UserTwapSliceFills(UserTwapSliceFills),
     Pong,
 }
 
@@ -165,6 +167,12 @@ impl WsManager {
                 .map_err(|e| Error::JsonParse(e.to_string()))
             }
             Message::Notification(_) => Ok("notification".to_string()),
+            Message::UserTwapSliceFills(user_twap_slice_fills) => {
+                serde_json::to_string(&Subscription::UserTwapSliceFills {
+                    user: user_twap_slice_fills.data.user,
+                })
+                .map_err(|e| Error::JsonParse(e.to_string()))
+            }
             Message::SubscriptionResponse | Message::Pong => Ok(String::default()),
         }
     }